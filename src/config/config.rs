@@ -1,9 +1,10 @@
 use log::{error, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::fs;
 
-use crate::{config::error::ConfigError, hyprland::{hyprctl::get_monitors, structs::MonitorName}};
+use crate::{config::error::ConfigError, hyprland::{hyprctl::get_monitors, structs::MonitorName}, system::terminal::detect_terminal};
 
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -18,6 +19,241 @@ pub struct ShunpoConfig {
     pub editor_dispatch: String,
     pub editor_term: String,
     pub editor: String,
+    #[serde(default)]
+    pub bar: BarConfig,
+    /// Blur launcher results while a screencast is active.
+    #[serde(default)]
+    pub screencast_hide_results: bool,
+    #[serde(default)]
+    pub launch: LaunchConfig,
+    /// Per-app launch settings.
+    #[serde(default)]
+    pub apps: Vec<AppConfig>,
+    /// External commands providing results for a search prefix.
+    #[serde(default)]
+    pub providers: Vec<ProviderConfig>,
+    #[serde(default)]
+    pub clipboard: ClipboardConfig,
+    #[serde(default)]
+    pub emoji: EmojiConfig,
+    #[serde(default)]
+    pub bookmarks: BookmarksConfig,
+    #[serde(default)]
+    pub web_search: WebSearchConfig,
+    #[serde(default)]
+    pub ssh: SshConfig,
+}
+
+/// Launch behaviour shared by all apps.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchConfig {
+    /// Focus an existing window of a desktop app instead of starting another instance.
+    pub run_or_raise: bool,
+    /// How apps are started, see `LaunchMethod`.
+    pub method: LaunchMethod,
+    /// Keep the terminal open after scripts from `script_paths` exit.
+    pub hold_scripts: bool,
+    /// Report launches as failed when no window opens within `verify_window_timeout_ms`.
+    pub verify_window: bool,
+    pub verify_window_timeout_ms: u64,
+    /// Background scripts are stopped after running this long.
+    pub background_timeout_secs: u64,
+}
+impl Default for LaunchConfig {
+    fn default() -> Self {
+        LaunchConfig {
+            run_or_raise: false,
+            method: LaunchMethod::Exec,
+            hold_scripts: false,
+            verify_window: true,
+            verify_window_timeout_ms: 5000,
+            background_timeout_secs: 300,
+        }
+    }
+}
+
+/// How apps are started.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LaunchMethod {
+    /// Child of Hyprland, through `hyprctl dispatch exec`.
+    Exec,
+    /// In a transient user scope per launch, through `systemd-run --user --scope`.
+    Systemd,
+}
+
+/// Launch settings for one app, applied whenever it is launched from Shunpo.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    /// Desktop file id (file name without `.desktop`), or executable file name.
+    pub id: String,
+    /// Hyprland exec rules, e.g. `workspace 2 silent` or `float`.
+    #[serde(default)]
+    pub exec_rules: Vec<String>,
+    /// Window class to raise, when it differs from the desktop entry's `StartupWMClass`.
+    pub raise_class: Option<String>,
+    /// Overrides `launch.run_or_raise` for this app.
+    pub run_or_raise: Option<bool>,
+    /// Overrides `launch.method` for this app.
+    pub method: Option<LaunchMethod>,
+    /// Keep the terminal open after a terminal app exits.
+    pub hold: Option<bool>,
+    /// Working directory, overriding the desktop entry's `Path`.
+    pub cwd: Option<PathBuf>,
+    /// Environment variables set for this app, on top of the environment Shunpo was started with.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+/// Clipboard history, searched with the `c ` prefix.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardConfig {
+    /// Watch the clipboard with `wl-paste --watch` and keep a history. Off unless opted in.
+    pub enabled: bool,
+    /// Oldest entries are dropped beyond this.
+    pub max_entries: usize,
+    /// Entries older than this are dropped, 0 keeps them.
+    pub max_age_days: u64,
+    /// Larger copies are not stored.
+    pub max_entry_bytes: usize,
+    /// Copies offering any of these types are not stored, besides the password manager hint.
+    pub excluded_mime_types: Vec<String>,
+}
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        ClipboardConfig {
+            enabled: false,
+            max_entries: 200,
+            max_age_days: 7,
+            max_entry_bytes: 64 * 1024,
+            excluded_mime_types: vec![String::from("application/x-nspasswordmanager")],
+        }
+    }
+}
+
+/// Emoji and character picker, searched with the `:e ` prefix.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmojiConfig {
+    pub action: EmojiAction,
+}
+
+/// What choosing a character does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmojiAction {
+    /// Put it on the clipboard.
+    #[default]
+    Copy,
+    /// Type it into the window focused before the launcher, with `wtype`.
+    Type,
+}
+
+/// Bookmarks, searched with the `bm ` prefix.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BookmarksConfig {
+    /// Bookmark files, `.toml` with `[[bookmarks]]` tables, or markdown links.
+    pub files: Vec<String>,
+    /// Read bookmarks of Firefox profiles, needs `sqlite3`.
+    pub firefox: bool,
+    /// Read bookmarks of Chromium, Chrome, Brave and Vivaldi profiles.
+    pub chromium: bool,
+}
+
+/// Web searches started with an engine's keyword, e.g. `ddg rust`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSearchConfig {
+    pub engines: Vec<SearchEngineConfig>,
+    /// Keyword of the engine offered below the results of every app search, none when unset.
+    pub fallback: Option<String>,
+}
+impl Default for WebSearchConfig {
+    fn default() -> Self {
+        WebSearchConfig {
+            engines: vec![
+                SearchEngineConfig::new("DuckDuckGo", "ddg", "https://duckduckgo.com/?q={query}"),
+                SearchEngineConfig::new("GitHub", "gh", "https://github.com/search?q={query}"),
+                SearchEngineConfig::new("docs.rs", "docs", "https://docs.rs/releases/search?query={query}"),
+            ],
+            fallback: Some(String::from("ddg")),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SearchEngineConfig {
+    /// Shown in result rows.
+    pub name: String,
+    /// Typed before the search terms, followed by a space.
+    pub keyword: String,
+    /// `{query}` is replaced by the percent-encoded search terms.
+    pub url: String,
+}
+impl SearchEngineConfig {
+    pub fn new(name: &str, keyword: &str, url: &str) -> Self {
+        SearchEngineConfig { name: name.to_string(), keyword: keyword.to_string(), url: url.to_string() }
+    }
+}
+
+/// SSH hosts, searched with the `ssh ` prefix. Hosts of `~/.ssh/config` are always listed.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SshConfig {
+    /// Also list hosts from `~/.ssh/known_hosts`, except hashed entries.
+    pub known_hosts: bool,
+}
+
+/// External result provider, see `providers::external`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProviderConfig {
+    /// Shown with errors and in result rows.
+    pub name: String,
+    /// Queries starting with this are sent to the provider, e.g. `gh `.
+    pub prefix: String,
+    /// Run with `sh -c`, the query is passed as the last argument.
+    pub command: String,
+    /// Results are dropped when the command takes longer, 1000 when unset.
+    pub timeout_ms: Option<u64>,
+}
+
+/// Optional segments of the clock-mode bar.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BarConfig {
+    pub urgent_app_name: bool,
+    pub active_window: bool,
+    /// `{class}` and `{title}` are replaced with those of the focused window.
+    pub active_window_format: String,
+    /// Maximum characters shown, longer text is truncated with an ellipsis.
+    pub active_window_max_len: usize,
+    /// Show the current submap while not in the default one.
+    pub submap: bool,
+    pub keyboard_layout: bool,
+    /// Only follow layout changes of this keyboard (see `hyprctl devices`), all keyboards when unset.
+    pub keyboard_name: Option<String>,
+    /// Short names shown for layouts, keyed by the layout name reported by Hyprland.
+    pub keyboard_layouts: HashMap<String, String>,
+    /// Show an indicator while the screen or a window is shared.
+    pub screencast: bool,
+}
+impl Default for BarConfig {
+    fn default() -> Self {
+        BarConfig {
+            urgent_app_name: true,
+            active_window: false,
+            active_window_format: String::from("{class}: {title}"),
+            active_window_max_len: 60,
+            submap: true,
+            keyboard_layout: false,
+            keyboard_name: None,
+            keyboard_layouts: HashMap::new(),
+            screencast: true,
+        }
+    }
 }
 
 impl ShunpoConfig {
@@ -67,6 +303,16 @@ impl ShunpoConfig {
             editor_dispatch,
             editor_term,
             editor,
+            bar: BarConfig::default(),
+            screencast_hide_results: false,
+            launch: LaunchConfig::default(),
+            apps: Vec::new(),
+            providers: Vec::new(),
+            clipboard: ClipboardConfig::default(),
+            emoji: EmojiConfig::default(),
+            bookmarks: BookmarksConfig::default(),
+            web_search: WebSearchConfig::default(),
+            ssh: SshConfig::default(),
         };
 
         config.save_config()?;
@@ -87,16 +333,10 @@ impl ShunpoConfig {
     }
 
     fn collect_terminals() -> Result<String, ConfigError> {
-        let candidates = [
-            "/usr/bin/ghostty",
-            "/usr/bin/kitty",
-            "/usr/bin/alacritty",
-        ];
-
-        candidates
-            .iter()
-            .find(|path| std::path::Path::new(path).exists())
-            .map(|s| s.to_string())
+        let path_env = std::env::var("PATH").unwrap_or_default();
+
+        detect_terminal(&path_env)
+            .map(|p| p.to_string_lossy().to_string())
             .ok_or(ConfigError::NoSupportedTerminal)
     }
 }
@@ -142,6 +382,14 @@ impl ShunpoConfig {
     }
 }
 //
+// queries
+//
+impl ShunpoConfig {
+    pub fn app(&self, id: &str) -> Option<&AppConfig> {
+        self.apps.iter().find(|app| app.id == id)
+    }
+}
+//
 // validation
 //
 impl ShunpoConfig {
@@ -0,0 +1,55 @@
+use std::{collections::VecDeque, time::{Duration, Instant}};
+
+/// A launch waiting for its window to open.
+pub struct PendingLaunch {
+    pub name: String,
+    /// Window classes the launched app may open its window with
+    pub classes: Vec<String>,
+    pub deadline: Instant,
+}
+
+/// Matches launched apps to Hyprland `openwindow` events by window class.
+/// Launches without a window before their deadline are reported as failed.
+#[derive(Default)]
+pub struct LaunchTracker {
+    pending: VecDeque<PendingLaunch>,
+}
+impl LaunchTracker {
+    pub fn track(&mut self, name: String, classes: Vec<String>, now: Instant, timeout: Duration) {
+        self.pending.push_back(PendingLaunch {
+            name,
+            classes,
+            deadline: now + timeout,
+        });
+    }
+    /// A window of `class` opened, resolving the oldest launch expecting that class.
+    /// Windows of other apps leave the pending launches alone.
+    pub fn window_opened(&mut self, class: &str) -> Option<PendingLaunch> {
+        let index = self.pending.iter()
+            .position(|p| p.classes.iter().any(|c| class_matches(c, class)))?;
+        self.pending.remove(index)
+    }
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.iter().map(|p| p.deadline).min()
+    }
+    /// Remove and return launches whose deadline has passed.
+    pub fn expire(&mut self, now: Instant) -> Vec<PendingLaunch> {
+        let (expired, pending): (Vec<_>, Vec<_>) = self.pending
+            .drain(..)
+            .partition(|p| p.deadline <= now);
+        self.pending = pending.into();
+        expired
+    }
+}
+
+/// Case-insensitive, and reverse-DNS classes match their last part, e.g.
+/// `org.wezfurlong.wezterm` is the class of a window launched as `wezterm`.
+fn class_matches(expected: &str, class: &str) -> bool {
+    let (expected, class) = (expected.to_lowercase(), class.to_lowercase());
+    if expected.is_empty() || class.is_empty() {
+        return false;
+    }
+    expected == class
+        || class.ends_with(&format!(".{}", expected))
+        || expected.ends_with(&format!(".{}", class))
+}
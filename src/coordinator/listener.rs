@@ -1,11 +1,16 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::{Path, PathBuf}, time::{Duration, Instant}};
 
 use log::{error, info};
 use tokio::sync::mpsc;
 
-use crate::{config::config::ShunpoConfig, coordinator::{error::CoordinatorError, types::{
+use crate::{config::config::{LaunchMethod, ShunpoConfig}, coordinator::{error::CoordinatorError, launch_tracker::LaunchTracker, types::{
     CoordinatorMessage, FeedbackData, GuiMessage, HyprlandEventData, SearchMessageData, ShunpoSocketEventData
-}}, hyprland::hyprctl::{dispatch, dispatch_from_term}, search::entity_model::{CustomDispatcher, Dispatcher, EntityFields, Export}};
+}}, hyprland::hyprctl::{dispatch, dispatch_from_term, raise_client, ExecOptions}, providers::clipboard::{delete_clip, SharedHistory}, search::{entity_model::{CustomDispatcher, Dispatcher, EntityFields, ExecutableSource, Export, FileEntity, LauncherEntity, VirtualAction, VirtualEntity}, launch_options::{append_args, resolve_hold, resolve_working_dir}}, system::{background::{run_job, JobHandle, JobOutput, JobStatus}, clipboard::copy_to_clipboard, error::LaunchError, launch::{command_program, missing_program, shell_quote, spawn_tracked, SHORT_LIVED_GRACE}, launch_env::LaunchEnv, systemd::{app_scope_unit, unit_suffix}, terminal::TerminalCommand}};
+
+/// Time for the launcher to hide before typing into the focused window
+const TYPE_TEXT_DELAY: Duration = Duration::from_millis(150);
+/// Output of a running background job is shown at most this often
+const JOB_REFRESH: Duration = Duration::from_millis(100);
 
 pub async fn coordinator_run(
     hyprland_rx: mpsc::UnboundedReceiver<CoordinatorMessage>,
@@ -14,6 +19,7 @@ pub async fn coordinator_run(
     gui_tx: async_channel::Sender<GuiMessage>,
     feedback_rx: mpsc::UnboundedReceiver<CoordinatorMessage>,
     config: ShunpoConfig,
+    clipboard: SharedHistory,
 ) {
     tokio::spawn(async move {
         if let Err(e) = coordinator_listener(
@@ -23,6 +29,7 @@ pub async fn coordinator_run(
             search_coord_rx,
             feedback_rx,
             config,
+            clipboard,
         ).await {
             error!("Coordinator loop exited with error: {:?}", e);
         }
@@ -36,14 +43,33 @@ async fn coordinator_listener(
     mut search_coord_rx: mpsc::UnboundedReceiver<CoordinatorMessage>,
     mut feedback_rx: mpsc::UnboundedReceiver<CoordinatorMessage>,
     config: ShunpoConfig,
+    clipboard: SharedHistory,
 ) -> Result<(),Box<dyn std::error::Error + Send + Sync>> {
 
     let rg_dispatcher = crate::rg::dispatcher::from_config_or_default(&config);
+    let mut tracker = LaunchTracker::default();
+    let mut job: Option<JobHandle> = None;
 
     loop {
+        let next_deadline = tracker.next_deadline();
         tokio::select! {
-            Some(CoordinatorMessage::HyprlandEvent(msg)) = hyprland_rx.recv()
-            => { log_error(handle_hyprland(msg, &gui_tx).await, "Hyprland handler"); },
+            Some(msg) = hyprland_rx.recv()
+            => {
+                match msg {
+                    CoordinatorMessage::HyprlandEvent(msg) => {
+                        log_error(handle_hyprland(msg, &gui_tx).await, "Hyprland handler");
+                    },
+                    CoordinatorMessage::WindowOpened(class) => {
+                        if let Some(launch) = tracker.window_opened(&class.to_string()) {
+                            info!("Window opened for: {}", launch.name);
+                        }
+                    },
+                    _ => error!("Unexpected message on the Hyprland channel."),
+                }
+            },
+
+            _ = sleep_until_deadline(next_deadline), if next_deadline.is_some()
+            => { log_error(handle_expired_launches(&mut tracker, &gui_tx).await, "Launch tracker"); },
 
             Some(CoordinatorMessage::ShunpoSocketEvent(msg)) = shunpo_rx.recv()
             => { log_error(handle_shunpo_socket(msg, &gui_tx).await, "Socket handler"); },
@@ -52,7 +78,7 @@ async fn coordinator_listener(
             => { log_error(handle_search(msg, &gui_tx).await, "Search handler"); },
 
             Some(CoordinatorMessage::Feedback(msg)) = feedback_rx.recv()
-            => { log_error(handle_feedback(msg, &rg_dispatcher, &gui_tx, &config).await, "Feedback handler"); },
+            => { log_error(handle_feedback(msg, &rg_dispatcher, &mut tracker, &mut job, &clipboard, &gui_tx, &config).await, "Feedback handler"); },
 
             else => {
                 info!("All input channels closed. Exiting coordinator loop.");
@@ -75,6 +101,17 @@ async fn handle_hyprland(
     Ok(())
 }
 
+async fn handle_expired_launches(
+    tracker: &mut LaunchTracker,
+    gui_tx: &async_channel::Sender<GuiMessage>,
+) -> Result<(), CoordinatorError> {
+    for launch in tracker.expire(Instant::now()) {
+        error!("No window of class {:?} appeared for: {}", launch.classes, launch.name);
+        gui_tx.send(GuiMessage::LaunchFailed(format!("{}: {}", launch.name, LaunchError::NoWindow))).await?;
+    }
+    Ok(())
+}
+
 async fn handle_shunpo_socket(
     msg: ShunpoSocketEventData,
     gui_tx: &async_channel::Sender<GuiMessage>,
@@ -100,12 +137,27 @@ async fn handle_search(
 async fn handle_feedback(
     msg: FeedbackData,
     rg_dispatcher: &CustomDispatcher,
+    tracker: &mut LaunchTracker,
+    job: &mut Option<JobHandle>,
+    clipboard: &SharedHistory,
     gui_tx: &async_channel::Sender<GuiMessage>,
     config: &ShunpoConfig,
 ) -> Result<(), CoordinatorError> {
     let gui_cmd = match msg {
         FeedbackData::GuiMessagePassthrough(g) => { g }
+        FeedbackData::CancelJob => {
+            if let Some(job) = job.take().filter(JobHandle::is_running) {
+                info!("Cancelling background job: {}", job.name);
+                job.cancel();
+            }
+            return Ok(());
+        },
+        FeedbackData::DeleteClip(text) => {
+            delete_clip(clipboard, &text);
+            return Ok(());
+        },
         FeedbackData::Run(run) => {
+            let run = *run;
 
             // TODO: proper arg parsing
             // ignoring launch args for now
@@ -114,15 +166,93 @@ async fn handle_feedback(
                 .trim_end_matches("%U")
                 .trim_end_matches("%f")
                 .trim().to_string();
+            let cmd = append_args(&cmd, &run.launch.args);
+
+            // background jobs keep the launcher open to show their output
+            if matches!(run.dispatcher, Dispatcher::Background) {
+                return start_background_job(&run, cmd, job, gui_tx, config).await;
+            }
+
+            // focus a running instance instead of launching another
+            if should_raise(&run, config) {
+                match raise_client(&raise_classes(&run, config)) {
+                    Ok(true) => {
+                        gui_tx.send(GuiMessage::Sleep).await?;
+                        return Ok(());
+                    },
+                    Ok(false) => {},
+                    Err(e) => error!("Failed to raise existing window: {}", e),
+                }
+            }
+
+            let rules = exec_rules(&run, config);
+            let env = launch_env(&run, config);
+            let scope = scope_unit(&run, config);
+            let in_terminal = matches!(run.dispatcher, Dispatcher::Shell);
+            let cwd = working_dir(&run, config, in_terminal);
+
+            // a missing program fails right away, Hyprland doesn't report it
+            if matches!(run.dispatcher, Dispatcher::Hyprctl | Dispatcher::Shell)
+                && let Some(program) = missing_program(&cmd, &env)
+            {
+                let err = LaunchError::NotFound(program);
+                error!("Launch failed: {}: {}", run.file_entity.ui_name(), err);
+                gui_tx.send(GuiMessage::LaunchFailed(
+                    format!("Launch failed: {}: {}", run.file_entity.ui_name(), err)
+                )).await?;
+                return Ok(());
+            }
+            let options = ExecOptions {
+                rules: &rules,
+                env: &env,
+                scope: scope.as_deref(),
+                cwd: None,
+            };
 
             let dispatch = match run.dispatcher {
-                Dispatcher::Shell => { dispatch_from_term(&cmd) },
-                Dispatcher::Hyprctl => { dispatch(&cmd) },
+                Dispatcher::Shell => {
+                    // the terminal is given the working directory, so its shell starts there too
+                    let command = TerminalCommand {
+                        title: Some(run.file_entity.ui_name().clone()),
+                        cwd,
+                        hold: hold_terminal(&run, config),
+                        ..TerminalCommand::new(&cmd)
+                    };
+                    dispatch_from_term(&command, &config.terminal_path, &options)
+                },
+                Dispatcher::Hyprctl => { dispatch(&cmd, &ExecOptions { cwd: cwd.as_deref(), ..options }) },
+                Dispatcher::Background => unreachable!("background jobs are started before dispatching"),
                 Dispatcher::Virtual => {
-                    crate::hyprpaper_client::paper_client::set_random_wallpaper(&config);
-                    log::warn!("Virtual dispatch set random wallpapers without branching");
+                    match &run.file_entity {
+                        FileEntity::Virtual(v) => match &v.action {
+                            VirtualAction::RandomWallpaper => {
+                                crate::hyprpaper_client::paper_client::set_random_wallpaper(&config);
+                            },
+                            VirtualAction::CopyToClipboard(text) | VirtualAction::RestoreClip(text) => {
+                                if let Err(e) = copy_to_clipboard(text) {
+                                    error!("Copy to clipboard failed: {}", e);
+                                    gui_tx.send(GuiMessage::LaunchFailed(format!("Copy failed: {}", e))).await?;
+                                    return Ok(());
+                                }
+                            },
+                            VirtualAction::TypeText(text) => spawn_type_text(text.clone(), gui_tx.clone()),
+                            // informational results keep the launcher open
+                            VirtualAction::None => return Ok(()),
+                        },
+                        FileEntity::Provider(_) => return Ok(()),
+                        _ => error!("Non-virtual entity requested the virtual dispatcher."),
+                    }
                     Ok(())
                 },
+                Dispatcher::HyprCommand => {
+                    match &run.file_entity {
+                        FileEntity::HyprCommand(h) => h.command.dispatch(),
+                        _ => {
+                            error!("Non-command entity requested the HyprCommand dispatcher.");
+                            Ok(())
+                        },
+                    }
+                },
                 Dispatcher::Custom => {
                     match &run.file_entity {
                         crate::search::entity_model::FileEntity::Ripgrep(ripgrep_entity) => {
@@ -138,10 +268,7 @@ async fn handle_feedback(
 
                             if let Some(call) = rg_dispatcher.compose_dispatch(args) {
                                 info!("ripgrep dispatcher will call: {:?}", call);
-                                let _ = std::process::Command::new("sh")
-                                    .arg("-c")
-                                    .arg(&call)
-                                    .spawn();
+                                spawn_custom_dispatch(call, ripgrep_entity.ui_name.clone(), gui_tx.clone());
                             }
                             else {
                                 error!("Failed to compose dispatch for ripgrep.");
@@ -153,6 +280,12 @@ async fn handle_feedback(
                         crate::search::entity_model::FileEntity::Virtual(_) => {
                             error!("Executable entity requested a custom dispatcher.");
                         },
+                        crate::search::entity_model::FileEntity::HyprCommand(_) => {
+                            error!("HyprCommand entity requested a custom dispatcher.");
+                        },
+                        crate::search::entity_model::FileEntity::Provider(_) => {
+                            error!("Provider entity requested a custom dispatcher.");
+                        },
                     }
                     Ok(())
                 },
@@ -160,6 +293,16 @@ async fn handle_feedback(
             match dispatch {
                 Ok(_) => {
                     info!("Dispatched: {}", &cmd);
+                    if should_verify_window(&run, config) {
+                        let classes = window_classes(&run, &cmd, config);
+                        let entity = run.file_entity.into_entity();
+                        tracker.track(
+                            entity.ui_name().clone(),
+                            classes,
+                            Instant::now(),
+                            Duration::from_millis(config.launch.verify_window_timeout_ms),
+                        );
+                    }
                 },
                 Err(e) => {
                     error!("Dispatch failed: {}", e);
@@ -167,6 +310,10 @@ async fn handle_feedback(
                     error!("-> ui_name: {}", entity.ui_name());
                     error!("-> path: {}", entity.path().to_string_lossy());
                     error!("-> dispatcher: {}", entity.dispatcher());
+                    // keep the launcher open so the failure is visible
+                    gui_tx.send(GuiMessage::LaunchFailed(
+                        format!("Launch failed: {}: {}", entity.ui_name(), e)
+                    )).await?;
                     return Err(CoordinatorError::FeedbackError(e.to_string()))
                 },
             }
@@ -183,6 +330,211 @@ async fn handle_feedback(
 // helpers
 //
 
+/// Only launches which are expected to open a window are tracked.
+fn should_verify_window(run: &LauncherEntity, config: &ShunpoConfig) -> bool {
+    config.launch.verify_window
+        && matches!(run.file_entity, FileEntity::Executable(_))
+        && matches!(run.dispatcher, Dispatcher::Hyprctl | Dispatcher::Shell)
+}
+
+/// Run a background script off the coordinator loop, showing its output in the launcher it was started from.
+/// Only one job runs at a time.
+async fn start_background_job(
+    run: &LauncherEntity,
+    cmd: String,
+    job: &mut Option<JobHandle>,
+    gui_tx: &async_channel::Sender<GuiMessage>,
+    config: &ShunpoConfig,
+) -> Result<(), CoordinatorError> {
+    if let Some(running) = job.as_ref().filter(|j| j.is_running()) {
+        gui_tx.send(GuiMessage::LaunchFailed(format!("{}: {}", running.name, LaunchError::JobRunning))).await?;
+        return Ok(());
+    }
+
+    let name = run.file_entity.ui_name().clone();
+    let (handle, cancel) = JobHandle::new(name.clone());
+    *job = Some(handle);
+
+    let env = launch_env(run, config);
+    let cwd = working_dir(run, config, false);
+    let timeout = Duration::from_secs(config.launch.background_timeout_secs);
+    let gui_tx = gui_tx.clone();
+    info!("Starting background job: {}", &cmd);
+
+    tokio::spawn(async move {
+        let mut output = JobOutput::default();
+        let mut last_shown = Instant::now();
+        let _ = gui_tx.send(GuiMessage::UpdateJob(Some(name.clone()))).await;
+        let _ = gui_tx.send(job_results(&name, "running…", &output)).await;
+
+        let status = run_job(&cmd, &env, cwd.as_deref(), timeout, cancel, |line| {
+            output.push(line);
+            if last_shown.elapsed() >= JOB_REFRESH {
+                last_shown = Instant::now();
+                let _ = gui_tx.try_send(job_results(&name, "running…", &output));
+            }
+        }).await;
+
+        info!("Background job {}: {}", name, status);
+        let _ = gui_tx.send(job_results(&name, &status.to_string(), &output)).await;
+        let _ = gui_tx.send(GuiMessage::UpdateJob(None)).await;
+        // also visible after the launcher was closed
+        if !matches!(status, JobStatus::Exited(Some(0)) | JobStatus::Cancelled) {
+            let _ = gui_tx.send(GuiMessage::LaunchFailed(format!("{}: {}", name, status))).await;
+        }
+    });
+    Ok(())
+}
+
+/// Status line followed by the job's output
+fn job_results(name: &str, status: &str, output: &JobOutput) -> GuiMessage {
+    let results = std::iter::once(format!("{}: {}", name, status))
+        .chain(output.lines().cloned())
+        .map(|text| LauncherEntity::from_virtual(&VirtualEntity::no_dispatch(text)))
+        .collect();
+    GuiMessage::JobOutput(SearchMessageData::composed(results))
+}
+
+/// Run a custom dispatch call off the coordinator loop, reporting early failures to the UI.
+fn spawn_custom_dispatch(call: String, name: String, gui_tx: async_channel::Sender<GuiMessage>) {
+    tokio::spawn(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            spawn_tracked(&call, SHORT_LIVED_GRACE, &LaunchEnv::original())
+        }).await;
+
+        let err = match result {
+            Ok(Ok(())) => return,
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => e.to_string(),
+        };
+        error!("Custom dispatch failed: {}: {}", name, err);
+        let _ = gui_tx.send(GuiMessage::LaunchFailed(format!("Launch failed: {}: {}", name, err))).await;
+    });
+}
+
+/// Type text with `wtype` once the launcher is hidden and the previous window has keyboard focus again.
+fn spawn_type_text(text: String, gui_tx: async_channel::Sender<GuiMessage>) {
+    tokio::spawn(async move {
+        tokio::time::sleep(TYPE_TEXT_DELAY).await;
+        let call = format!("wtype -- {}", shell_quote(&text));
+        let result = tokio::task::spawn_blocking(move || {
+            spawn_tracked(&call, SHORT_LIVED_GRACE, &LaunchEnv::original())
+        }).await;
+
+        let err = match result {
+            Ok(Ok(())) => return,
+            Ok(Err(e)) => e.to_string(),
+            Err(e) => e.to_string(),
+        };
+        error!("Typing text failed: {}", err);
+        let _ = gui_tx.send(GuiMessage::LaunchFailed(format!("Typing failed: {}", err))).await;
+    });
+}
+
+async fn sleep_until_deadline(deadline: Option<Instant>) {
+    if let Some(deadline) = deadline {
+        tokio::time::sleep_until(tokio::time::Instant::from_std(deadline)).await;
+    }
+}
+
+/// Run or raise applies to desktop apps, unless disabled or forced to start a new instance.
+fn should_raise(run: &LauncherEntity, config: &ShunpoConfig) -> bool {
+    let FileEntity::Executable(e) = &run.file_entity else {
+        return false;
+    };
+    if run.launch.force_new || !matches!(e.source, ExecutableSource::DesktopFile) {
+        return false;
+    }
+    config.app(&e.app_id())
+        .and_then(|app| app.run_or_raise)
+        .unwrap_or(config.launch.run_or_raise)
+}
+
+/// Window classes identifying an app: configured class, `StartupWMClass`, then desktop file id.
+fn raise_classes(run: &LauncherEntity, config: &ShunpoConfig) -> Vec<String> {
+    let FileEntity::Executable(e) = &run.file_entity else {
+        return Vec::new();
+    };
+    let app_id = e.app_id();
+    config.app(&app_id)
+        .and_then(|app| app.raise_class.clone())
+        .into_iter()
+        .chain(e.wm_class.clone())
+        .chain(Some(app_id))
+        .collect()
+}
+
+/// Window classes a tracked launch may open: those identifying the app, and the program
+/// it runs, which for terminal launches is the terminal.
+fn window_classes(run: &LauncherEntity, cmd: &str, config: &ShunpoConfig) -> Vec<String> {
+    let program = match run.dispatcher {
+        Dispatcher::Shell => config.terminal_path.split_whitespace().next(),
+        _ => command_program(cmd),
+    };
+    let program = program
+        .and_then(|p| Path::new(p).file_name())
+        .map(|name| name.to_string_lossy().to_string());
+    raise_classes(run, config).into_iter()
+        .chain(program)
+        .collect()
+}
+
+/// Exec rules from the app's config, followed by those chosen for this launch.
+fn exec_rules(run: &LauncherEntity, config: &ShunpoConfig) -> Vec<String> {
+    let mut rules = match &run.file_entity {
+        FileEntity::Executable(e) => config.app(&e.app_id())
+            .map(|app| app.exec_rules.clone())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    rules.extend(run.launch.exec_rules.iter().cloned());
+    rules
+}
+
+/// Original environment with the app's configured variables.
+fn launch_env(run: &LauncherEntity, config: &ShunpoConfig) -> LaunchEnv {
+    let env = LaunchEnv::original();
+    match &run.file_entity {
+        FileEntity::Executable(e) => match config.app(&e.app_id()) {
+            Some(app) => env.with_overrides(&app.env),
+            None => env,
+        },
+        _ => env,
+    }
+}
+
+/// Working directory of a launch, see `resolve_working_dir`.
+fn working_dir(run: &LauncherEntity, config: &ShunpoConfig, in_terminal: bool) -> Option<PathBuf> {
+    let FileEntity::Executable(e) = &run.file_entity else {
+        return None;
+    };
+    let home = std::env::var("HOME").ok().map(PathBuf::from);
+    resolve_working_dir(e, &run.launch, config.app(&e.app_id()), in_terminal, home.as_deref())
+}
+
+/// Keep the terminal open after the command exits, see `resolve_hold`.
+fn hold_terminal(run: &LauncherEntity, config: &ShunpoConfig) -> bool {
+    let FileEntity::Executable(e) = &run.file_entity else {
+        return run.launch.hold;
+    };
+    resolve_hold(e, &run.launch, config.app(&e.app_id()), config.launch.hold_scripts)
+}
+
+/// Scope unit to launch an app in, when the systemd launch method applies to it.
+fn scope_unit(run: &LauncherEntity, config: &ShunpoConfig) -> Option<String> {
+    let FileEntity::Executable(e) = &run.file_entity else {
+        return None;
+    };
+    let app_id = e.app_id();
+    let method = config.app(&app_id)
+        .and_then(|app| app.method)
+        .unwrap_or(config.launch.method);
+    match method {
+        LaunchMethod::Systemd => Some(app_scope_unit(&app_id, unit_suffix())),
+        LaunchMethod::Exec => None,
+    }
+}
+
 fn log_error<T, E: std::fmt::Display>(result: Result<T, E>, context: &str) {
     if let Err(e) = result {
         error!("{}: {}", context, e);
@@ -1,3 +1,4 @@
 pub mod error;
+pub mod launch_tracker;
 pub mod listener;
 pub mod types;
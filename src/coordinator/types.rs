@@ -1,4 +1,4 @@
-use crate::{hyprland::structs::{LayerLevel, MonitorName}, search::entity_model::LauncherEntity};
+use crate::{hyprland::structs::{LayerLevel, MonitorName, WindowClass, WorkspaceId}, search::entity_model::LauncherEntity};
 
 //
 // coordinator inbound messages
@@ -8,6 +8,8 @@ pub enum CoordinatorMessage {
     ShunpoSocketEvent(ShunpoSocketEventData),
     SearchMessage(SearchMessageData),
     Feedback(FeedbackData),
+    /// A window opened, with its class
+    WindowOpened(WindowClass),
 }
 pub struct HyprlandEventData {
     pub gui_msg: GuiMessage,
@@ -17,10 +19,30 @@ pub enum ShunpoSocketEventData {
 }
 pub struct SearchMessageData {
     pub results: Vec<LauncherEntity>,
+    /// Query the search listener answered, None for results composed by the UI
+    pub query: Option<String>,
+}
+impl SearchMessageData {
+    /// Results composed by the UI or the coordinator, always shown
+    pub fn composed(results: Vec<LauncherEntity>) -> Self {
+        SearchMessageData { results, query: None }
+    }
+    /// Search results are stale once the field holds another query, and while arguments are
+    /// entered, where they would replace the choices.
+    pub fn is_current(&self, search: &str, prompting: bool) -> bool {
+        match &self.query {
+            Some(query) => !prompting && query == search,
+            None => true,
+        }
+    }
 }
 pub enum FeedbackData {
     GuiMessagePassthrough(GuiMessage),
-    Run(LauncherEntity),
+    Run(Box<LauncherEntity>),
+    /// Stop the running background job
+    CancelJob,
+    /// Remove an entry from the clipboard history
+    DeleteClip(String),
 }
 
 //
@@ -34,13 +56,35 @@ pub enum GuiMessage {
     Sleep,
     DeepSleep,
     DisplayResults(SearchMessageData),
-    UpdateWorkspace(Vec<WorkspaceMessage>),
+    UpdateWorkspace(Vec<MonitorWorkspacesMessage>),
+    UpdateUrgent(Option<String>),
+    UpdateActiveWindow(Option<String>),
+    UpdateSubmap(Option<String>),
+    UpdateKeyboardLayout(Option<String>),
+    UpdateScreencast { text: Option<String>, hide_results: bool },
+    LaunchFailed(String),
+    /// Name of the running background job
+    UpdateJob(Option<String>),
+    /// Status and output of the running background job
+    JobOutput(SearchMessageData),
     WaylandMonitorLayer { target_monitor: MonitorName, target_layer: LayerLevel },
 }
 
-#[derive(Clone)]
-pub struct WorkspaceMessage {
-    pub id: String,
-    pub focused: bool,
+/// Workspaces on a single monitor, in the order they are displayed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MonitorWorkspacesMessage {
+    pub monitor: MonitorName,
     pub xpos: i32,
+    pub workspaces: Vec<WorkspaceMessage>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkspaceMessage {
+    pub id: WorkspaceId,
+    pub label: String,      // workspace name, without the `special:` prefix
+    pub active: bool,       // shown on its monitor
+    pub focused: bool,      // shown on the focused monitor
+    pub occupied: bool,
+    pub urgent: bool,
+    pub special: bool,
 }
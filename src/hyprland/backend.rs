@@ -0,0 +1,32 @@
+use crate::hyprland::{
+    error::HyprError,
+    hyprctl::{get_clients, get_layers, get_monitors, get_workspaces},
+    structs::{Client, Layers, Monitor, Workspace},
+};
+
+/// Source of hyprctl snapshots used by HyprlandState.
+/// Allows replacing hyprctl with recorded snapshots when replaying event logs.
+pub trait HyprctlBackend: Send + Sync {
+    fn monitors(&self) -> Result<Vec<Monitor>, HyprError>;
+    fn workspaces(&self) -> Result<Vec<Workspace>, HyprError>;
+    fn layers(&self) -> Result<Layers, HyprError>;
+    fn clients(&self) -> Result<Vec<Client>, HyprError>;
+}
+
+/// Queries a running Hyprland instance by calling hyprctl.
+pub struct HyprctlCli;
+
+impl HyprctlBackend for HyprctlCli {
+    fn monitors(&self) -> Result<Vec<Monitor>, HyprError> {
+        get_monitors()
+    }
+    fn workspaces(&self) -> Result<Vec<Workspace>, HyprError> {
+        get_workspaces()
+    }
+    fn layers(&self) -> Result<Layers, HyprError> {
+        get_layers()
+    }
+    fn clients(&self) -> Result<Vec<Client>, HyprError> {
+        get_clients()
+    }
+}
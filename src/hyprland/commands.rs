@@ -0,0 +1,230 @@
+use std::fmt::Display;
+
+use crate::hyprland::{
+    error::HyprError,
+    hyprctl::{get_active_window, get_option_str, hyprctl_ok, WindowTarget},
+};
+
+/// Window management commands run from the launcher, e.g. `:ws 3` or `:float`
+#[derive(Clone, Debug, PartialEq)]
+pub enum HyprCommand {
+    Workspace(WorkspaceArg),
+    MoveToWorkspace(WorkspaceArg),
+    ToggleFloating,
+    Fullscreen,
+    Kill,
+    Pin,
+    /// Switch to the given layout, or toggle between dwindle and master
+    Layout(Option<Layout>),
+}
+
+/// Command names, usage and description, as listed in launcher hints
+pub const HYPR_COMMANDS: &[(&str, &str, &str)] = &[
+    ("ws", ":ws <workspace>", "Go to workspace"),
+    ("movetows", ":movetows <workspace>", "Move active window to workspace"),
+    ("float", ":float", "Toggle floating for active window"),
+    ("fullscreen", ":fullscreen", "Toggle fullscreen for active window"),
+    ("kill", ":kill", "Close active window"),
+    ("pin", ":pin", "Pin active floating window to all workspaces"),
+    ("layout", ":layout [dwindle|master]", "Switch or toggle layout"),
+];
+
+/// Workspace argument accepted by the workspace dispatchers.
+/// Bare names are prefixed with `name:`, other forms are passed to Hyprland as is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkspaceArg(String);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Layout {
+    Dwindle,
+    Master,
+}
+
+//
+// parsing
+//
+
+impl HyprCommand {
+    /// Parse a launcher query such as `:ws 3`.
+    /// Returns None if the query is not a command, and an error if its arguments are invalid.
+    pub fn parse(query: &str) -> Option<Result<HyprCommand, HyprError>> {
+        let query = query.strip_prefix(":")?.trim();
+        let (name, args) = query.split_once(' ')
+            .map(|(name, args)| (name, args.trim()))
+            .unwrap_or((query, ""));
+
+        let cmd = match name {
+            "ws" => WorkspaceArg::parse(args).map(HyprCommand::Workspace),
+            "movetows" => WorkspaceArg::parse(args).map(HyprCommand::MoveToWorkspace),
+            "float" => no_args(args, HyprCommand::ToggleFloating),
+            "fullscreen" => no_args(args, HyprCommand::Fullscreen),
+            "kill" => no_args(args, HyprCommand::Kill),
+            "pin" => no_args(args, HyprCommand::Pin),
+            "layout" => Layout::parse(args).map(HyprCommand::Layout),
+            _ => return None,
+        };
+        Some(cmd)
+    }
+    /// Text shown as the launcher result before dispatching
+    pub fn preview(&self) -> String {
+        match self {
+            HyprCommand::Workspace(ws) => format!("Go to workspace {}", ws),
+            HyprCommand::MoveToWorkspace(ws) => format!("Move active window to workspace {}", ws),
+            HyprCommand::ToggleFloating => "Toggle floating for active window".into(),
+            HyprCommand::Fullscreen => "Toggle fullscreen for active window".into(),
+            HyprCommand::Kill => "Close active window".into(),
+            HyprCommand::Pin => "Pin active window".into(),
+            HyprCommand::Layout(Some(layout)) => format!("Switch to {} layout", layout),
+            HyprCommand::Layout(None) => "Toggle between dwindle and master layout".into(),
+        }
+    }
+}
+fn no_args(args: &str, cmd: HyprCommand) -> Result<HyprCommand, HyprError> {
+    if !args.is_empty() {
+        return Err(HyprError::CommandInvalidArgs(format!("unexpected arguments: {}", args)));
+    }
+    Ok(cmd)
+}
+
+impl WorkspaceArg {
+    pub fn parse(arg: &str) -> Result<WorkspaceArg, HyprError> {
+        let arg = arg.trim();
+        if arg.is_empty() {
+            return Err(HyprError::CommandInvalidArgs("missing workspace".into()));
+        }
+        if arg.contains(char::is_whitespace) || arg.contains(',') {
+            return Err(HyprError::CommandInvalidArgs(format!("invalid workspace: {}", arg)));
+        }
+
+        let is_relative = |s: &str| {
+            s.len() > 1
+                && (s.starts_with('+') || s.starts_with('-'))
+                && s[1..].chars().all(|c| c.is_ascii_digit())
+        };
+
+        // id, relative (+1, -1, e+1, m-1), special or named workspace
+        if let Ok(id) = arg.parse::<i32>() {
+            if id < 1 {
+                return Err(HyprError::CommandInvalidArgs(format!("invalid workspace id: {}", id)));
+            }
+            Ok(WorkspaceArg(arg.into()))
+        }
+        else if is_relative(arg)
+            || ["e", "m", "r"].iter().any(|p| arg.strip_prefix(p).is_some_and(is_relative))
+            || ["previous", "empty", "special"].contains(&arg)
+            || arg.starts_with("name:")
+            || arg.starts_with("special:")
+        {
+            Ok(WorkspaceArg(arg.into()))
+        }
+        else {
+            Ok(WorkspaceArg(format!("name:{}", arg)))
+        }
+    }
+}
+impl Display for WorkspaceArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Layout {
+    pub fn parse(arg: &str) -> Result<Option<Layout>, HyprError> {
+        match arg.trim() {
+            "" => Ok(None),
+            "dwindle" => Ok(Some(Layout::Dwindle)),
+            "master" => Ok(Some(Layout::Master)),
+            other => Err(HyprError::CommandInvalidArgs(format!("unknown layout: {}", other))),
+        }
+    }
+}
+impl Display for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Layout::Dwindle => write!(f, "dwindle"),
+            Layout::Master => write!(f, "master"),
+        }
+    }
+}
+
+//
+// dispatch
+//
+
+impl HyprCommand {
+    /// hyprctl arguments for this command, targeting `window` where a window is required.
+    /// `current_layout` is only read when toggling the layout.
+    pub fn hyprctl_args(
+        &self,
+        window: &WindowTarget,
+        current_layout: Option<&str>,
+    ) -> Vec<String> {
+        match self {
+            HyprCommand::Workspace(ws) => {
+                vec!["dispatch".into(), "workspace".into(), ws.to_string()]
+            },
+            HyprCommand::MoveToWorkspace(ws) => {
+                vec!["dispatch".into(), "movetoworkspace".into(), format!("{},{}", ws, window.selector())]
+            },
+            HyprCommand::ToggleFloating => {
+                vec!["dispatch".into(), "togglefloating".into(), window.selector()]
+            },
+            // fullscreen only acts on the focused window
+            HyprCommand::Fullscreen => {
+                vec!["dispatch".into(), "fullscreen".into(), "0".into()]
+            },
+            HyprCommand::Kill => {
+                vec!["dispatch".into(), "closewindow".into(), window.selector()]
+            },
+            HyprCommand::Pin => {
+                vec!["dispatch".into(), "pin".into(), window.selector()]
+            },
+            HyprCommand::Layout(layout) => {
+                let layout = match layout {
+                    Some(layout) => layout.clone(),
+                    None if current_layout == Some("master") => Layout::Dwindle,
+                    None => Layout::Master,
+                };
+                vec!["keyword".into(), "general:layout".into(), layout.to_string()]
+            },
+        }
+    }
+    fn targets_window(&self) -> bool {
+        matches!(self,
+            HyprCommand::MoveToWorkspace(_)
+            | HyprCommand::ToggleFloating
+            | HyprCommand::Kill
+            | HyprCommand::Pin
+        )
+    }
+    /// Run the command with hyprctl, resolving the active window by address.
+    pub fn dispatch(&self) -> Result<(), HyprError> {
+        let mut window = WindowTarget::Active;
+        if self.targets_window() {
+            let client = get_active_window()?.ok_or(HyprError::WindowAddrNotFound)?;
+            window = WindowTarget::Address(client.address);
+        }
+        let current_layout = match self {
+            HyprCommand::Layout(None) => Some(get_option_str("general:layout")?),
+            _ => None,
+        };
+
+        let args = self.hyprctl_args(&window, current_layout.as_deref());
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        hyprctl_ok(&args)
+    }
+}
+impl Display for HyprCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HyprCommand::Workspace(ws) => write!(f, "workspace {}", ws),
+            HyprCommand::MoveToWorkspace(ws) => write!(f, "movetoworkspace {}", ws),
+            HyprCommand::ToggleFloating => write!(f, "togglefloating"),
+            HyprCommand::Fullscreen => write!(f, "fullscreen"),
+            HyprCommand::Kill => write!(f, "closewindow"),
+            HyprCommand::Pin => write!(f, "pin"),
+            HyprCommand::Layout(Some(layout)) => write!(f, "layout {}", layout),
+            HyprCommand::Layout(None) => write!(f, "layout toggle"),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 pub enum HyprError {
+    CommandInvalidArgs(String),
     EventParseFailed,
+    HyprCtlDispatchFailed(String),
     HyprCtlDispatchTerm,
     HyprCtlExec(String),
     HyprCtlExecDecode(String),
@@ -11,17 +13,22 @@ pub enum HyprError {
     InstanceSignatureNotSet(String),
     MonitorIdNotFound,
     ParseIntError,
+    RecorderIo(String),
+    RecorderSerde(String),
     ShunpoInvariantAllFullscreen,
     ShunpoInvariantTargetTopScore,
     ShunpoNotFound,
     ShunpoTargetNoSolution,
+    WindowAddrNotFound,
     WorkspaceIdNotFound,
     XdgRuntimeDir(String),
 }
 impl std::fmt::Display for HyprError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
+            HyprError::CommandInvalidArgs(e) => write!(f, "CommandInvalidArgs: {}", e),
             HyprError::EventParseFailed => write!(f, "EventParseFailed"),
+            HyprError::HyprCtlDispatchFailed(e) => write!(f, "HyprCtlDispatchFailed: {}", e),
             HyprError::HyprCtlDispatchTerm => write!(f, "HyprCtlDispatchTerm"),
             HyprError::HyprCtlExec(e) => write!(f, "HyprCtlExec: {}", e),
             HyprError::HyprCtlExecDecode(e) => write!(f, "HyprCtlExecDecode: {}", e),
@@ -33,10 +40,13 @@ impl std::fmt::Display for HyprError {
             HyprError::InstanceSignatureNotSet(e) => write!(f, "HYPRLAND_INSTANCE_SIGNATURE not set {}", e),
             HyprError::MonitorIdNotFound => write!(f, "MonitorIdNotFound"),
             HyprError::ParseIntError => write!(f, "ParseIntError"),
+            HyprError::RecorderIo(e) => write!(f, "RecorderIo: {}", e),
+            HyprError::RecorderSerde(e) => write!(f, "RecorderSerde: {}", e),
             HyprError::ShunpoInvariantAllFullscreen => write!(f, "ShunpoInvariantAllFullscreen"),
             HyprError::ShunpoInvariantTargetTopScore => write!(f, "ShunpoInvariantTopScore"),
             HyprError::ShunpoNotFound => write!(f, "ShunpoNotFound"),
             HyprError::ShunpoTargetNoSolution => write!(f, "ShunpoTargetNoSolution"),
+            HyprError::WindowAddrNotFound => write!(f, "WindowAddrNotFound"),
             HyprError::WorkspaceIdNotFound => write!(f, "WorkspaceIdNotFound"),
             HyprError::XdgRuntimeDir(e) => write!(f, "XdgRuntimeDir: Unable to open socket: {}", e),
         }
@@ -132,7 +132,7 @@ impl HyprlandEvent {
             "monitoraddedv2" => {
                 let (mid, mname, mdesc) = value_split_twice(value, event_delimiter)?;
 
-                Ok(HyprlandEvent::Monitorremovedv2 {
+                Ok(HyprlandEvent::Monitoraddedv2 {
                     mid: MonitorId::try_from(mid)?,
                     mname: MonitorName::from(mname),
                     mdesc: MonitorDesc::from(mdesc),
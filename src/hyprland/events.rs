@@ -1,3 +1,4 @@
+use std::{collections::HashMap, path::PathBuf};
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
     net::UnixStream,
@@ -5,12 +6,14 @@ use tokio::{
 };
 use log::{error, info};
 
-use crate::{config::config::ShunpoConfig, coordinator::types::{CoordinatorMessage, GuiMessage, HyprlandEventData, WorkspaceMessage}, hyprland::{error::HyprError, event_parser::HyprlandEvent, state::HyprlandState}};
+use crate::{config::config::ShunpoConfig, coordinator::types::{CoordinatorMessage, GuiMessage, HyprlandEventData, MonitorWorkspacesMessage, WorkspaceMessage}, hyprland::{error::HyprError, event_parser::HyprlandEvent, recorder::{EventRecorder, HyprctlSnapshot}, state::HyprlandState, structs::{KeyboardName, LayoutName, Monitor, Owner, State, SubmapName}}};
 
-/// Subscribe to Hyprland events
+/// Subscribe to Hyprland events.
+/// When `record_path` is set, every event is written to a session file for replay in tests.
 pub async fn subscribe_events(
     tx: UnboundedSender<CoordinatorMessage>,
-    config: ShunpoConfig
+    config: ShunpoConfig,
+    record_path: Option<PathBuf>,
 ) -> Result<(), HyprError> {
     // get env vars
     let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE")
@@ -29,12 +32,31 @@ pub async fn subscribe_events(
     let mut state: HyprlandState = HyprlandState::default();
     state.config = Some(config);
 
+    let mut recorder = match record_path {
+        Some(path) => {
+            info!("Recording Hyprland events to: {}", path.to_string_lossy());
+            Some(EventRecorder::create(&path)?)
+        },
+        None => None,
+    };
+
     info!("Listening for Hyprland events...");
 
     while let Some(line) = lines
         .next_line().await
         .map_err(|e| HyprError::HyprlandSocketListen(e.to_string()))? {
 
+        if let Some(recorder) = recorder.as_mut() {
+            // hyprctl is queried off the runtime, before the event changes the state
+            let backend = state.backend.clone();
+            let snapshot = tokio::task::spawn_blocking(move || HyprctlSnapshot::capture(backend.as_ref()))
+                .await
+                .unwrap_or_else(|e| Err(HyprError::RecorderIo(e.to_string())));
+            if let Err(e) = snapshot.and_then(|snapshot| recorder.record_snapshot(&line, snapshot)) {
+                error!("Failed to record event: {}", e);
+            }
+        }
+
         if let Ok(event) = HyprlandEvent::parse_event(&line) {
             for message in update_state(&mut state, event) {
                 let _ = tx.send(message);
@@ -47,7 +69,8 @@ pub async fn subscribe_events(
 
     Ok(())
 }
-fn update_state(state: &mut HyprlandState, event: HyprlandEvent) -> Vec<CoordinatorMessage> {
+/// Apply a Hyprland event to `state` and collect the resulting messages for the coordinator.
+pub fn update_state(state: &mut HyprlandState, event: HyprlandEvent) -> Vec<CoordinatorMessage> {
     match event {
         // rebuild state when wayland layers with shunpo namespace are created.
         // occurs on app launch for initial state, and on changes to or from deep sleep mode
@@ -56,6 +79,14 @@ fn update_state(state: &mut HyprlandState, event: HyprlandEvent) -> Vec<Coordina
                 if let Err(e) = state.rebuild() {
                     error!("An error occurred while rebuilding HyprlandState: {}", e);
                 }
+                let mut msg = update_workspaces(state);
+                if active_window_enabled(state) {
+                    if let Err(e) = state.rebuild_active_window() {
+                        error!("Openlayer: Failed to find active window: {}", e);
+                    }
+                    msg.extend(update_active_window(state));
+                }
+                return msg;
             }
         },
         // monitor focus change
@@ -78,8 +109,92 @@ fn update_state(state: &mut HyprlandState, event: HyprlandEvent) -> Vec<Coordina
             }
             return update_workspaces(state);
         },
+        // window requested attention
+        HyprlandEvent::Urgent { waddr } => {
+            if let Err(e) = state.add_urgent_window(waddr) {
+                error!("Urgent: Failed to resolve window: {}", e);
+                return vec![];
+            }
+            let mut msg = update_workspaces(state);
+            msg.extend(update_urgent(state));
+            return msg;
+        },
+        // window focus and title changes
+        HyprlandEvent::Activewindow { winclass, wintitle } => {
+            state.set_active_window(winclass, wintitle);
+            return update_active_window(state);
+        },
+        HyprlandEvent::Windowtitlev2 { waddr, wintitle } => {
+            if state.update_active_window_title(&waddr, wintitle) {
+                return update_active_window(state);
+            }
+        },
+        // submap entered, empty when back to the default one
+        HyprlandEvent::Submap { smname } => {
+            return update_submap(state, smname);
+        },
+        // keyboard layout switched
+        HyprlandEvent::Activelayout { kname, lname } => {
+            return update_keyboard_layout(state, kname, lname);
+        },
+        // screen or window sharing started or stopped
+        HyprlandEvent::Screencast { state: cast_state, owner } => {
+            return update_screencast(state, cast_state, owner);
+        },
+        // focusing or closing an urgent window clears it
+        HyprlandEvent::Activewindowv2 { winaddr } => {
+            state.set_active_window_addr(&winaddr);
+            if state.clear_urgent_window(&winaddr) {
+                let mut msg = update_workspaces(state);
+                msg.extend(update_urgent(state));
+                return msg;
+            }
+        },
+        HyprlandEvent::Closewindow { waddr } => {
+            let cleared = state.clear_urgent_window(&waddr);
+            if let Err(e) = state.rebuild_workspaces() {
+                error!("Closewindow: Failed to rebuild workspaces: {}", e);
+            }
+            let mut msg = update_workspaces(state);
+            if cleared {
+                msg.extend(update_urgent(state));
+            }
+            return msg;
+        },
+        HyprlandEvent::Movewindowv2 { waddr, wid, .. } => {
+            state.move_urgent_window(&waddr, wid);
+            if let Err(e) = state.rebuild_workspaces() {
+                error!("Movewindowv2: Failed to rebuild workspaces: {}", e);
+            }
+            return update_workspaces(state);
+        },
+        // new window, also resolves launches waiting for a window
+        HyprlandEvent::Openwindow { winclass, .. } => {
+            if let Err(e) = state.rebuild_workspaces() {
+                error!("Openwindow: Failed to rebuild workspaces: {}", e);
+            }
+            let mut msg = update_workspaces(state);
+            msg.push(CoordinatorMessage::WindowOpened(winclass));
+            return msg;
+        },
+        // workspace occupancy, naming or placement changed
+        HyprlandEvent::Destroyworkspacev2 { .. }
+        | HyprlandEvent::Moveworkspacev2 { .. }
+        | HyprlandEvent::Renameworkspace { .. } => {
+            if let Err(e) = state.rebuild_workspaces() {
+                error!("Failed to rebuild workspaces: {}", e);
+            }
+            return update_workspaces(state);
+        },
+        // special workspace toggled, tracked by monitors
+        HyprlandEvent::Activespecialv2 { .. } => {
+            if let Err(e) = state.rebuild_monitors().and_then(|_| state.rebuild_workspaces()) {
+                error!("Activespecialv2: Failed to rebuild state: {}", e);
+            }
+            return update_workspaces(state);
+        },
         HyprlandEvent::Monitoraddedv2 { .. } => {
-            if let Err(e) = state.rebuild_monitors() {
+            if let Err(e) = state.rebuild_monitors().and_then(|_| state.rebuild_workspaces()) {
                 error!("Monitoraddedv2: Failed to rebuild monitors: {}", e);
             }
             return update_workspaces(state);
@@ -119,21 +234,133 @@ fn update_workspaces(state: &mut HyprlandState) -> Vec<CoordinatorMessage> {
         return vec![];
     }
 
-    let mut msg: Vec<WorkspaceMessage> = state.monitors
+    let mut msg: Vec<MonitorWorkspacesMessage> = state.monitors
         .iter()
-        .map(|(_, monitor)| WorkspaceMessage {
-            id: monitor.active_workspace.id.to_string(),
-            focused: match &state.focused_monitor {
-                Some(fmon) => fmon == &monitor.name,
-                None => false,
-            },
+        .map(|(_, monitor)| MonitorWorkspacesMessage {
+            monitor: monitor.name.clone(),
             xpos: monitor.x,
+            workspaces: workspace_messages(state, monitor),
         })
         .collect();
-    msg.sort_by_key(|ws| ws.xpos);
+    msg.sort_by_key(|m| m.xpos);
 
     vec![package_gui_message(GuiMessage::UpdateWorkspace(msg))]
 }
+fn workspace_messages(state: &HyprlandState, monitor: &Monitor) -> Vec<WorkspaceMessage> {
+    let monitor_focused = match &state.focused_monitor {
+        Some(fmon) => fmon == &monitor.name,
+        None => false,
+    };
+
+    state.workspaces_on_monitor(&monitor.name)
+        .into_iter()
+        .map(|workspace| {
+            let active = workspace.id == monitor.active_workspace.id
+                || workspace.id == monitor.special_workspace.id;
+
+            WorkspaceMessage {
+                id: workspace.id,
+                label: workspace.label(),
+                active,
+                focused: active && monitor_focused,
+                occupied: workspace.windows > 0,
+                urgent: state.is_workspace_urgent(&workspace.id),
+                special: workspace.is_special(),
+            }
+        })
+        .collect()
+}
+fn update_urgent(state: &HyprlandState) -> Vec<CoordinatorMessage> {
+    if !state.config.as_ref().is_some_and(|c| c.bar.urgent_app_name) {
+        return vec![];
+    }
+
+    let mut classes: Vec<String> = state.urgent_windows
+        .values()
+        .map(|w| w.class.to_string())
+        .collect();
+    classes.sort();
+    classes.dedup();
+
+    let text = (!classes.is_empty()).then(|| classes.join(", "));
+    vec![package_gui_message(GuiMessage::UpdateUrgent(text))]
+}
+fn active_window_enabled(state: &HyprlandState) -> bool {
+    state.config.as_ref().is_some_and(|c| c.bar.active_window)
+}
+fn update_active_window(state: &HyprlandState) -> Vec<CoordinatorMessage> {
+    let Some(bar) = state.config.as_ref().map(|c| &c.bar).filter(|b| b.active_window) else {
+        return vec![];
+    };
+
+    let text = state.active_window
+        .as_ref()
+        .map(|w| {
+            let text = bar.active_window_format
+                .replace("{class}", &w.class.to_string())
+                .replace("{title}", &w.title.to_string());
+            truncate_chars(&text, bar.active_window_max_len)
+        });
+    vec![package_gui_message(GuiMessage::UpdateActiveWindow(text))]
+}
+/// Truncate to `max` characters, ending with an ellipsis when shortened.
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+fn update_submap(state: &HyprlandState, smname: SubmapName) -> Vec<CoordinatorMessage> {
+    if !state.config.as_ref().is_some_and(|c| c.bar.submap) {
+        return vec![];
+    }
+
+    let name = smname.to_string();
+    let text = (!name.is_empty()).then_some(name);
+    vec![package_gui_message(GuiMessage::UpdateSubmap(text))]
+}
+fn update_keyboard_layout(state: &HyprlandState, kname: KeyboardName, lname: LayoutName) -> Vec<CoordinatorMessage> {
+    let Some(bar) = state.config.as_ref().map(|c| &c.bar).filter(|b| b.keyboard_layout) else {
+        return vec![];
+    };
+    if bar.keyboard_name.as_ref().is_some_and(|name| name != &kname.to_string()) {
+        return vec![];
+    }
+
+    let text = layout_short_name(&lname.to_string(), &bar.keyboard_layouts);
+    vec![package_gui_message(GuiMessage::UpdateKeyboardLayout(Some(text)))]
+}
+/// Configured short name for a layout, or the first two letters of the layout name.
+/// e.g. `English (US)` -> `EN`
+fn layout_short_name(layout: &str, short_names: &HashMap<String, String>) -> String {
+    match short_names.get(layout) {
+        Some(short) => short.clone(),
+        None => layout.chars().take(2).collect::<String>().to_uppercase(),
+    }
+}
+fn update_screencast(state: &HyprlandState, cast_state: State, owner: Owner) -> Vec<CoordinatorMessage> {
+    let Some(config) = state.config.as_ref() else {
+        return vec![];
+    };
+    if !config.bar.screencast && !config.screencast_hide_results {
+        return vec![];
+    }
+
+    let active = cast_state.to_string() == "1";
+    let text = (active && config.bar.screencast).then(|| {
+        match owner.to_string().as_str() {
+            "0" => String::from("Sharing monitor"),
+            "1" => String::from("Sharing window"),
+            other => format!("Sharing {}", other),
+        }
+    });
+    vec![package_gui_message(GuiMessage::UpdateScreencast {
+        text,
+        hide_results: active && config.screencast_hide_results,
+    })]
+}
 fn package_gui_message(gui_msg: GuiMessage) -> CoordinatorMessage {
     CoordinatorMessage::HyprlandEvent(HyprlandEventData { gui_msg })
 }
@@ -1,15 +1,22 @@
 #![allow(dead_code)]
 
 use log::{error, info};
-use serde::de::DeserializeOwned;
-use std::{env, process::Command};
-
-use crate::hyprland::{error::HyprError, structs::{Client, Layers, Monitor, Workspace}};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{path::Path, process::Command};
+
+use crate::{
+    hyprland::{error::HyprError, structs::{Client, Layers, Monitor, WindowAddr, Workspace}},
+    system::{
+        launch::shell_quote,
+        launch_env::LaunchEnv,
+        systemd::in_scope,
+        terminal::{compose_terminal, TerminalCommand},
+    },
+};
 
 /// Execute a hyprctl command and return the output
 pub fn hyprctl(args: &[&str]) -> Result<String, HyprError> {
     let output = Command::new("hyprctl")
-        .env_remove("GSK_RENDERER") // TODO: TODO_PRESERVE_ENV
         .args(args)
         .arg("-j") // JSON output
         .output()
@@ -43,54 +50,149 @@ pub fn get_layers() -> Result<Layers, HyprError> {
     from_json_or_panic(&output, "get_layers")
 }
 
+/// Execute a hyprctl command which replies `ok` on success, e.g. `dispatch` or `keyword`
+pub fn hyprctl_ok(args: &[&str]) -> Result<(), HyprError> {
+    let output = hyprctl(args)?;
+    match output.trim() {
+        "ok" => Ok(()),
+        reply => Err(HyprError::HyprCtlDispatchFailed(reply.to_string())),
+    }
+}
+
+/// How a command is started through `hyprctl dispatch exec`
+pub struct ExecOptions<'a> {
+    /// Exec rules such as `workspace 3 silent`
+    pub rules: &'a [String],
+    pub env: &'a LaunchEnv,
+    /// systemd scope unit to run the command in
+    pub scope: Option<&'a str>,
+    /// Working directory, Hyprland's when unset
+    pub cwd: Option<&'a Path>,
+}
+
 /// Dispatch a Hyprland command
-pub fn dispatch(cmd: &str) -> Result<(), HyprError> {
-    hyprctl(&["dispatch", "exec", cmd])?;
-    Ok(())
+pub fn dispatch(cmd: &str, options: &ExecOptions) -> Result<(), HyprError> {
+    hyprctl_ok(&["dispatch", "exec", &compose_exec(cmd, options)])
 }
 
-/// Dispatch a terminal
-pub fn dispatch_from_term(bin: &str) -> Result<(), HyprError> {
-    // TODO: TODO_PRESERVE_ENV
-    if let Ok(term) = env::var("TERM_PROGRAM") {
-        info!("Dispatching: {}", bin);
-        hyprctl(&["dispatch", "exec", &format!("{} -e sh -c '{}'", term, bin)])?;
-        Ok(())
+/// Dispatch a command in a new window of the terminal `term`
+pub fn dispatch_from_term(command: &TerminalCommand, term: &str, options: &ExecOptions) -> Result<(), HyprError> {
+    if term.is_empty() {
+        error!("No terminal configured: set terminal_path");
+        return Err(HyprError::HyprCtlDispatchTerm);
     }
-    else {
-        error!("Failed to fetch environment variable: TERM_PROGRAM");
-        Err(HyprError::HyprCtlDispatchTerm)
+    info!("Dispatching: {}", command.cmd);
+    let cmd = compose_terminal(term, command);
+    hyprctl_ok(&["dispatch", "exec", &compose_exec(&cmd, options)])
+}
+
+/// Argument of `dispatch exec`: `[rules] cd dir && env K=V systemd-run … cmd`
+pub fn compose_exec(cmd: &str, options: &ExecOptions) -> String {
+    let cmd = match options.scope {
+        Some(unit) => in_scope(cmd, unit),
+        None => cmd.to_string(),
+    };
+    let cmd = options.env.wrap_exec(&cmd);
+    let cmd = match options.cwd {
+        Some(dir) => format!("cd {} && {}", shell_quote(&dir.to_string_lossy()), cmd),
+        None => cmd,
+    };
+    exec_with_rules(&cmd, options.rules)
+}
+
+/// Prefix `cmd` with exec rules: `[workspace 3 silent; float] cmd`
+pub fn exec_with_rules(cmd: &str, rules: &[String]) -> String {
+    if rules.is_empty() {
+        return cmd.to_string();
     }
+    format!("[{}] {}", rules.join("; "), cmd)
+}
+
+/// Window selector for dispatchers which take a window argument
+#[derive(Clone, Debug, PartialEq)]
+pub enum WindowTarget {
+    Active,
+    InitialTitle(String),
+    Address(WindowAddr),
+}
+impl WindowTarget {
+    pub fn selector(&self) -> String {
+        match self {
+            WindowTarget::Active => "activewindow".into(),
+            WindowTarget::InitialTitle(title) => format!("initialtitle:{}", title),
+            WindowTarget::Address(addr) => format!("address:0x{}", addr.normalized()),
+        }
+    }
+}
+
+/// Get the focused window, None if no window has focus
+pub fn get_active_window() -> Result<Option<Client>, HyprError> {
+    let output = hyprctl(&["activewindow"])?;
+    if output.trim() == "{}" {
+        return Ok(None);
+    }
+    from_json_or_panic(&output, "get_active_window").map(Some)
+}
+
+/// Get the string value of a config option, e.g. `general:layout`
+pub fn get_option_str(option: &str) -> Result<String, HyprError> {
+    #[derive(Deserialize)]
+    struct OptionStr { str: String }
+
+    let output = hyprctl(&["getoption", option])?;
+    from_json_or_panic::<OptionStr>(&output, "get_option_str").map(|o| o.str)
 }
 
 /// Toggle floating for a client
-pub fn toggle_floating_by_initialtitle(initial_title: &str) -> Result<(), HyprError> {
-    hyprctl(&["dispatch", "togglefloating",
-        &format!("initialtitle:{}", initial_title)]
-    )?;
+pub fn toggle_floating(target: &WindowTarget) -> Result<(), HyprError> {
+    hyprctl(&["dispatch", "togglefloating", &target.selector()])?;
     Ok(())
 }
 
 /// Resize a client
-pub fn resize_client_by_initialtitle(initial_title: &str, width: u16, height: u16) -> Result<(), HyprError> {
+pub fn resize_client(target: &WindowTarget, width: u16, height: u16) -> Result<(), HyprError> {
     hyprctl(&["dispatch", "resizewindowpixel", "exact",
         &width.to_string(),
         &height.to_string(),
-        &format!(",initialtitle:{}", initial_title)]
+        &format!(",{}", target.selector())]
     )?;
     Ok(())
 }
 
 /// Move a client
-pub fn move_client_by_initialtitle(initial_title: &str, width: u16, height: u16) -> Result<(), HyprError> {
+pub fn move_client(target: &WindowTarget, width: u16, height: u16) -> Result<(), HyprError> {
     hyprctl(&["dispatch", "movewindowpixel", "exact",
         &width.to_string(),
         &height.to_string(),
-        &format!(",initialtitle:{}", initial_title)]
+        &format!(",{}", target.selector())]
     )?;
     Ok(())
 }
 
+/// Focus a client, switching to its workspace
+pub fn focus_window(target: &WindowTarget) -> Result<(), HyprError> {
+    hyprctl_ok(&["dispatch", "focuswindow", &target.selector()])
+}
+
+/// Most recently focused client whose initial class matches one of `classes`, ignoring case
+pub fn find_client_by_class<'a>(clients: &'a [Client], classes: &[String]) -> Option<&'a Client> {
+    clients.iter()
+        .filter(|c| c.mapped)
+        .filter(|c| classes.iter().any(|class| class.eq_ignore_ascii_case(&c.initial_class)))
+        .min_by_key(|c| c.focus_history_id)
+}
+
+/// Focus an existing client of an app. Returns false if it has no windows.
+pub fn raise_client(classes: &[String]) -> Result<bool, HyprError> {
+    let clients = get_clients()?;
+    let Some(client) = find_client_by_class(&clients, classes) else {
+        return Ok(false);
+    };
+    info!("Raising existing window: {}", client.address);
+    focus_window(&WindowTarget::Address(client.address.clone()))?;
+    Ok(true)
+}
+
 /// Check if client is currently on a visible workspace
 pub fn is_client_visible(client_name: &str) -> bool {
     let (Ok(monitors), Ok(clients)) = (get_monitors(), get_clients()) else {
@@ -1,7 +1,10 @@
+pub mod backend;
+pub mod commands;
 pub mod error;
 pub mod event_parser;
 pub mod events;
 pub mod hyprctl;
+pub mod recorder;
 pub mod state;
 pub mod structs;
 
@@ -0,0 +1,95 @@
+use std::{fs::File, io::Write, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::hyprland::{
+    backend::HyprctlBackend,
+    error::HyprError,
+    structs::{Client, Layers, Monitor, Workspace},
+};
+
+/// hyprctl output at a point in time, as returned by a HyprctlBackend.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HyprctlSnapshot {
+    pub monitors: Vec<Monitor>,
+    pub workspaces: Vec<Workspace>,
+    pub layers: Layers,
+    #[serde(default)]
+    pub clients: Vec<Client>,
+}
+impl HyprctlSnapshot {
+    pub fn capture(backend: &dyn HyprctlBackend) -> Result<Self, HyprError> {
+        Ok(HyprctlSnapshot {
+            monitors: backend.monitors()?,
+            workspaces: backend.workspaces()?,
+            layers: backend.layers()?,
+            clients: backend.clients()?,
+        })
+    }
+}
+
+/// A raw `.socket2.sock` event line, with the hyprctl snapshot taken when it arrived.
+/// `snapshot` is only stored when hyprctl output changed since the previous event.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RecordedEvent {
+    pub event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<HyprctlSnapshot>,
+}
+
+/// Writes Hyprland events and hyprctl snapshots to a JSON lines session file.
+pub struct EventRecorder {
+    file: File,
+    last_snapshot: Option<String>,
+}
+impl EventRecorder {
+    pub fn create(path: &Path) -> Result<Self, HyprError> {
+        let file = File::create(path)
+            .map_err(|e| HyprError::RecorderIo(e.to_string()))?;
+
+        Ok(EventRecorder {
+            file,
+            last_snapshot: None,
+        })
+    }
+
+    /// Append `line` to the session, capturing a snapshot from `backend` if it has changed.
+    pub fn record(&mut self, line: &str, backend: &dyn HyprctlBackend) -> Result<(), HyprError> {
+        self.record_snapshot(line, HyprctlSnapshot::capture(backend)?)
+    }
+
+    /// Append `line` to the session with a snapshot captured by the caller, stored if it has changed.
+    pub fn record_snapshot(&mut self, line: &str, snapshot: HyprctlSnapshot) -> Result<(), HyprError> {
+        let serialized = serde_json::to_string(&snapshot)
+            .map_err(|e| HyprError::RecorderSerde(e.to_string()))?;
+
+        let changed = self.last_snapshot.as_ref() != Some(&serialized);
+        let record = RecordedEvent {
+            event: line.to_string(),
+            snapshot: changed.then_some(snapshot),
+        };
+        if changed {
+            self.last_snapshot = Some(serialized);
+        }
+
+        let mut out = serde_json::to_string(&record)
+            .map_err(|e| HyprError::RecorderSerde(e.to_string()))?;
+        out.push('\n');
+
+        self.file.write_all(out.as_bytes())
+            .and_then(|_| self.file.flush())
+            .map_err(|e| HyprError::RecorderIo(e.to_string()))
+    }
+}
+
+/// Parse a session file written by EventRecorder.
+pub fn load_session(contents: &str) -> Result<Vec<RecordedEvent>, HyprError> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str::<RecordedEvent>(line)
+                .map_err(|e| HyprError::RecorderSerde(e.to_string()))
+        })
+        .collect()
+}
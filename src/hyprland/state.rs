@@ -1,11 +1,11 @@
-use std::{cmp::Reverse, collections::HashMap};
+use std::{cmp::Reverse, collections::HashMap, sync::Arc};
 
 use log::error;
 
 use crate::{config::config::ShunpoConfig, hyprland::{
+    backend::{HyprctlBackend, HyprctlCli},
     error::HyprError,
-    hyprctl::{get_layers, get_monitors, get_workspaces},
-    structs::{ LayerLevel, Monitor, MonitorLayers, MonitorName, Namespace, Workspace, WorkspaceId }
+    structs::{ LayerLevel, Monitor, MonitorLayers, MonitorName, Namespace, WindowAddr, WindowClass, WindowTitle, Workspace, WorkspaceId }
 }};
 
 pub struct HyprlandState {
@@ -14,7 +14,22 @@ pub struct HyprlandState {
     pub workspaces: HashMap<WorkspaceId, Workspace>,
     pub shunpo_namespace: Namespace,
     pub focused_monitor: Option<MonitorName>,
+    pub urgent_windows: HashMap<WindowAddr, UrgentWindow>,
+    pub active_window: Option<ActiveWindow>,
     pub config: Option<ShunpoConfig>,
+    pub backend: Arc<dyn HyprctlBackend>,
+}
+/// Window which requested attention, keyed by normalized address in HyprlandState.
+pub struct UrgentWindow {
+    pub workspace: WorkspaceId,
+    pub class: WindowClass,
+}
+/// Focused window, tracked from activewindow events.
+/// `address` is unknown until the activewindowv2 event following activewindow arrives.
+pub struct ActiveWindow {
+    pub address: Option<WindowAddr>,
+    pub class: WindowClass,
+    pub title: WindowTitle,
 }
 //
 // constructor
@@ -27,7 +42,20 @@ impl Default for HyprlandState {
             workspaces: HashMap::<WorkspaceId, Workspace>::new(),
             shunpo_namespace: Namespace::from("shunpo"),
             focused_monitor: None,
+            urgent_windows: HashMap::<WindowAddr, UrgentWindow>::new(),
+            active_window: None,
             config: None,
+            backend: Arc::new(HyprctlCli),
+        }
+    }
+}
+impl HyprlandState {
+    /// Empty state which will query `backend` instead of hyprctl when rebuilding.
+    pub fn with_backend(backend: Arc<dyn HyprctlBackend>, config: Option<ShunpoConfig>) -> Self {
+        HyprlandState {
+            config,
+            backend,
+            ..Default::default()
         }
     }
 }
@@ -35,18 +63,22 @@ impl Default for HyprlandState {
 // rebuild
 //
 impl HyprlandState {
-    /// Replaces current state by calling .populate() to rebuild from hyprctl.
+    /// Replaces hyprctl derived state by calling .populate() to rebuild from hyprctl.
+    /// State tracked from events (urgent windows) is kept.
     pub fn rebuild(&mut self) -> Result<(), HyprError> {
-        let config = self.config.clone();
-        *self = Self::populate(config)?;
+        let snapshot = Self::populate(self.config.clone(), self.backend.clone())?;
+        self.layers = snapshot.layers;
+        self.monitors = snapshot.monitors;
+        self.workspaces = snapshot.workspaces;
+        self.focused_monitor = snapshot.focused_monitor;
         Ok(())
     }
     /// Query hyprctl to build a snapshot of current Hyprland state.
-    pub fn populate(config: Option<ShunpoConfig>) -> Result<HyprlandState, HyprError> {
+    pub fn populate(config: Option<ShunpoConfig>, backend: Arc<dyn HyprctlBackend>) -> Result<HyprlandState, HyprError> {
         // collect monitors, layers and workspaces from hyprctl
-        let layers = Self::fetch_hyprctl_layers()?;
-        let monitors = Self::fetch_hyprctl_monitors()?;
-        let workspaces = Self::fetch_hyprctl_workspaces()?;
+        let layers = Self::fetch_hyprctl_layers(backend.as_ref())?;
+        let monitors = Self::fetch_hyprctl_monitors(backend.as_ref())?;
+        let workspaces = Self::fetch_hyprctl_workspaces(backend.as_ref())?;
 
         // find focused monitor
         let focused_monitor = monitors
@@ -61,45 +93,60 @@ impl HyprlandState {
             workspaces,
             shunpo_namespace: Namespace::from("shunpo"),
             focused_monitor,
+            urgent_windows: HashMap::<WindowAddr, UrgentWindow>::new(),
+            active_window: None,
             config,
+            backend,
         })
     }
     pub fn rebuild_monitors(&mut self) -> Result<(), HyprError> {
-        let monitors = Self::fetch_hyprctl_monitors()?;
+        let monitors = Self::fetch_hyprctl_monitors(self.backend.as_ref())?;
         self.monitors = monitors;
         Ok(())
     }
     pub fn rebuild_workspaces(&mut self) -> Result<(), HyprError> {
-        let workspaces = Self::fetch_hyprctl_workspaces()?;
+        let workspaces = Self::fetch_hyprctl_workspaces(self.backend.as_ref())?;
         self.workspaces = workspaces;
         Ok(())
     }
+    /// Seed the active window from `hyprctl clients`, before any activewindow event arrived.
+    pub fn rebuild_active_window(&mut self) -> Result<(), HyprError> {
+        self.active_window = self.backend.clients()?
+            .into_iter()
+            .find(|c| c.mapped && c.focus_history_id == 0)
+            .map(|c| ActiveWindow {
+                address: Some(c.address.normalized()),
+                class: c.class,
+                title: c.title,
+            });
+        Ok(())
+    }
 }
 //
 // hyprctl callers
 //
 impl HyprlandState {
-    /// Fetch workspaces by calling `hyprctl workspaces`
-    pub fn fetch_hyprctl_workspaces() -> Result<HashMap<WorkspaceId, Workspace>, HyprError> {
-        let hyprtctl_workspaces = get_workspaces().map_err(|_| HyprError::HyprCtlFetchWorkspaces)?;
+    /// Fetch workspaces from the backend (`hyprctl workspaces`)
+    pub fn fetch_hyprctl_workspaces(backend: &dyn HyprctlBackend) -> Result<HashMap<WorkspaceId, Workspace>, HyprError> {
+        let hyprtctl_workspaces = backend.workspaces().map_err(|_| HyprError::HyprCtlFetchWorkspaces)?;
 
         Ok(hyprtctl_workspaces
             .into_iter()
             .map(|w| (w.id.clone(), w))
             .collect())
     }
-    /// Fetch monitors by calling `hyprctl monitors`
-    pub fn fetch_hyprctl_monitors() -> Result<HashMap<MonitorName, Monitor>, HyprError> {
-        let hyprctl_monitors = get_monitors().map_err(|_| HyprError::HyprCtlFetchMonitors)?;
+    /// Fetch monitors from the backend (`hyprctl monitors`)
+    pub fn fetch_hyprctl_monitors(backend: &dyn HyprctlBackend) -> Result<HashMap<MonitorName, Monitor>, HyprError> {
+        let hyprctl_monitors = backend.monitors().map_err(|_| HyprError::HyprCtlFetchMonitors)?;
 
         Ok(hyprctl_monitors
             .into_iter()
             .map(|m| (m.name.clone(), m))
             .collect())
     }
-    /// Fetch monitor layers
-    pub fn fetch_hyprctl_layers() -> Result<HashMap<MonitorName, MonitorLayers>, HyprError> {
-        let hyprctl_layers = get_layers().map_err(|_| HyprError::HyprCtlFetchLayers)?;
+    /// Fetch monitor layers from the backend (`hyprctl layers`)
+    pub fn fetch_hyprctl_layers(backend: &dyn HyprctlBackend) -> Result<HashMap<MonitorName, MonitorLayers>, HyprError> {
+        let hyprctl_layers = backend.layers().map_err(|_| HyprError::HyprCtlFetchLayers)?;
 
         Ok(hyprctl_layers.monitors)
     }
@@ -111,6 +158,72 @@ impl HyprlandState {
     pub fn update_focused_monitor(&mut self, monitor_name: MonitorName) {
         self.focused_monitor = Some(monitor_name);
     }
+    /// Resolve an urgent window to its workspace with `hyprctl clients`, and start tracking it.
+    pub fn add_urgent_window(&mut self, waddr: WindowAddr) -> Result<(), HyprError> {
+        let waddr = waddr.normalized();
+        let client = self.backend.clients()?
+            .into_iter()
+            .find(|c| c.address.normalized() == waddr)
+            .ok_or(HyprError::WindowAddrNotFound)?;
+
+        self.urgent_windows.insert(waddr, UrgentWindow {
+            workspace: client.workspace.id,
+            class: client.class,
+        });
+        Ok(())
+    }
+    /// Follow an urgent window moved to another workspace.
+    pub fn move_urgent_window(&mut self, waddr: &WindowAddr, workspace: WorkspaceId) {
+        if let Some(window) = self.urgent_windows.get_mut(&waddr.normalized()) {
+            window.workspace = workspace;
+        }
+    }
+    /// Focus moved to a window, or to none when both class and title are empty.
+    pub fn set_active_window(&mut self, class: WindowClass, title: WindowTitle) {
+        if class.to_string().is_empty() && title.to_string().is_empty() {
+            self.active_window = None;
+        } else {
+            self.active_window = Some(ActiveWindow { address: None, class, title });
+        }
+    }
+    pub fn set_active_window_addr(&mut self, waddr: &WindowAddr) {
+        if waddr.to_string().is_empty() {
+            self.active_window = None;
+        } else if let Some(window) = self.active_window.as_mut() {
+            window.address = Some(waddr.normalized());
+        }
+    }
+    /// Update the title if `waddr` is the active window. Returns true if it was.
+    pub fn update_active_window_title(&mut self, waddr: &WindowAddr, title: WindowTitle) -> bool {
+        match self.active_window.as_mut() {
+            Some(window) if window.address.as_ref() == Some(&waddr.normalized()) => {
+                window.title = title;
+                true
+            },
+            _ => false,
+        }
+    }
+    /// Stop tracking an urgent window. Returns true if it was tracked.
+    pub fn clear_urgent_window(&mut self, waddr: &WindowAddr) -> bool {
+        self.urgent_windows.remove(&waddr.normalized()).is_some()
+    }
+}
+//
+// queries
+//
+impl HyprlandState {
+    /// Workspaces on `monitor`, ordered by id with special workspaces last.
+    pub fn workspaces_on_monitor(&self, monitor: &MonitorName) -> Vec<&Workspace> {
+        let mut workspaces: Vec<&Workspace> = self.workspaces
+            .values()
+            .filter(|w| &w.monitor == monitor)
+            .collect();
+        workspaces.sort_by_key(|w| (w.is_special(), w.id));
+        workspaces
+    }
+    pub fn is_workspace_urgent(&self, id: &WorkspaceId) -> bool {
+        self.urgent_windows.values().any(|w| &w.workspace == id)
+    }
 }
 //
 // remove
@@ -25,6 +25,14 @@ string_type!(WindowClass);
 string_type!(WindowTitle);
 string_type!(WorkspaceName);
 
+impl WindowAddr {
+    /// Address without the `0x` prefix.
+    /// Events report addresses without it, hyprctl includes it.
+    pub fn normalized(&self) -> WindowAddr {
+        WindowAddr(self.0.trim_start_matches("0x").to_string())
+    }
+}
+
 pub enum FullscreenEvent {
     Exited,
     Entered,
@@ -56,8 +64,20 @@ pub struct Workspace {
     #[serde(rename = "ispersistent")]
     pub is_persistent: bool,
 }
+impl Workspace {
+    pub fn is_special(&self) -> bool {
+        self.name.0.starts_with("special")
+    }
+    /// Workspace name for display, without the `special:` prefix.
+    pub fn label(&self) -> String {
+        self.name.0
+            .strip_prefix("special:")
+            .unwrap_or(&self.name.0)
+            .to_string()
+    }
+}
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Client {
     pub address: WindowAddr,
     pub mapped: bool,
@@ -142,7 +162,7 @@ pub struct Monitor {
     pub available_modes: Vec<String>,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum LayerLevel {
     Bottom,
     Overlay,
@@ -156,18 +176,18 @@ impl std::fmt::Display for LayerLevel {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Layers {
     #[serde(flatten)]
     pub monitors: HashMap<MonitorName, MonitorLayers>, // monitor name
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct MonitorLayers {
     pub levels: Levels,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Levels {
     #[serde(rename = "0")]
     pub background: Vec<Layer>,
@@ -179,7 +199,7 @@ pub struct Levels {
     pub overlay: Vec<Layer>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Layer {
     pub address: String,
     pub x: i16,
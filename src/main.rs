@@ -5,6 +5,7 @@ mod config;
 mod coordinator;
 mod hyprland;
 mod hyprpaper_client;
+mod providers;
 mod rg;
 mod search;
 mod socket;
@@ -12,7 +13,7 @@ mod socket_error;
 mod system;
 mod ui_gtk4;
 
-use std::process::exit;
+use std::{path::PathBuf, process::exit};
 
 use gtk4::glib::ExitCode;
 use log::{info, error};
@@ -32,6 +33,9 @@ fn runtime() -> &'static Runtime {
 }
 
 fn main() -> ExitCode {
+    // keep the user's environment for launched apps, before shunpo changes it for itself
+    system::launch_env::capture_original_env();
+
     // setup logger
     env_logger::Builder::from_env(env_logger::Env::default()
         .default_filter_or("shunpo=info"))
@@ -55,18 +59,22 @@ fn main() -> ExitCode {
     // hyprland event listener to coordinator
     let (event_tx, event_rx) = mpsc::unbounded_channel::<CoordinatorMessage>();
     let cfg_hypr_events = config.clone();
+    let record_path = record_events_path();
     runtime().spawn(async move {
-        if let Err(e) = hyprland::events::subscribe_events(event_tx, cfg_hypr_events).await {
+        if let Err(e) = hyprland::events::subscribe_events(event_tx, cfg_hypr_events, record_path).await {
             error!("Error in Hyprland listener: {}", e);
         }
     });
 
+    // clipboard history, shared by search and coordinator
+    let clipboard = providers::clipboard::start_clipboard_history(&config.clipboard);
+
     // search to coordinator
     let (search_coord_tx, search_coord_rx) = mpsc::unbounded_channel::<CoordinatorMessage>();
     // anywhere to search
     let (search_tx, search_rx) = mpsc::unbounded_channel::<String>();
     // setup search
-    let _search_worker = setup_search_listener(search_rx, search_coord_tx, config.clone());
+    let _search_worker = setup_search_listener(search_rx, search_coord_tx, config.clone(), clipboard.clone());
 
     // coordinator to gui
     let (gui_tx, gui_rx) = async_channel::unbounded::<GuiMessage>();
@@ -77,19 +85,15 @@ fn main() -> ExitCode {
     let config_coord = config.clone();
     runtime().spawn(async move {
         // Pass gui_tx into the coordinator so it can send messages
-        coordinator_run(event_rx, shunpo_rx, search_coord_rx, gui_tx, feedback_rx, config_coord).await;
+        coordinator_run(event_rx, shunpo_rx, search_coord_rx, gui_tx, feedback_rx, config_coord, clipboard).await;
     });
 
     // setup renderer
     // NOTE: UNSAFE
     // prevent loading heavy nvidia/vulkan libraries by modifying global process environment.
-    // this setting is removed when applications are launched.
+    // launched apps get the original value back from the captured environment (see LaunchEnv).
     unsafe {
-
-        // TODO: TODO_PRESERVE_ENV
-        // capture value of GSK_RENDERER (if any) and set it back when launching apps
         std::env::set_var("GSK_RENDERER", "cairo");
-        std::env::set_var("TERM_PROGRAM", config.terminal_path);
     }
 
     // run GTK on the main thread, passing the receiver
@@ -129,3 +133,19 @@ fn setup_shunpo_socket_or_exit(shunpo_tx: mpsc::UnboundedSender<CoordinatorMessa
         }
     }
 }
+
+/// Session file path passed with `--record-events <path>`, used to capture Hyprland events for replay tests.
+fn record_events_path() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--record-events" {
+            let path = args.next();
+            if path.is_none() {
+                error!("--record-events requires a file path.");
+                exit(1)
+            }
+            return path.map(PathBuf::from);
+        }
+    }
+    None
+}
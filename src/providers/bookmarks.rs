@@ -0,0 +1,345 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use log::{error, warn};
+use nucleo::{Matcher, Utf32String};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::task::JoinHandle;
+
+use crate::{
+    config::config::BookmarksConfig,
+    providers::url::{as_url, open_url_command},
+    search::{
+        entity_model::{Dispatcher, Export, FileEntity, LauncherEntity, ProviderEntity},
+        launch_options::expand_home,
+        matcher_helpers::search_entity,
+    },
+};
+
+/// Queries starting with this search bookmarks
+pub const BOOKMARK_PREFIX: &str = "bm ";
+/// Bookmarks are read again on the next search after this long
+const RELOAD_AFTER: Duration = Duration::from_secs(60);
+const PROVIDER_NAME: &str = "Bookmarks";
+const CHROMIUM_DIRS: &[&str] = &["chromium", "google-chrome", "BraveSoftware/Brave-Browser", "vivaldi"];
+/// Bookmarks of Firefox, read from a copy of places.sqlite, as Firefox keeps the database locked
+const FIREFOX_QUERY: &str = "SELECT IFNULL(b.title, ''), p.url FROM moz_bookmarks b \
+    JOIN moz_places p ON p.id = b.fk WHERE b.type = 1 AND p.url NOT LIKE 'place:%'";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bookmark {
+    pub title: String,
+    pub url: String,
+    /// Folder names or tags, matched along with the title
+    pub tags: Vec<String>,
+}
+
+/// Bookmarks from all configured sources, read on a blocking thread and reloaded when stale
+#[derive(Default)]
+pub struct BookmarkStore {
+    entities: Vec<FileEntity>,
+    loaded: Option<Instant>,
+    reload: Option<JoinHandle<Vec<FileEntity>>>,
+}
+impl BookmarkStore {
+    /// The first load is waited for, stale bookmarks are searched while they are read again.
+    pub async fn entities(&mut self, config: &BookmarksConfig) -> &Vec<FileEntity> {
+        if self.reload.is_none() && self.loaded.is_none_or(|at| at.elapsed() >= RELOAD_AFTER) {
+            let config = config.clone();
+            self.reload = Some(tokio::task::spawn_blocking(move || {
+                load_bookmarks(&config).into_iter()
+                    .map(|b| FileEntity::Provider(bookmark_entity(b)))
+                    .collect()
+            }));
+        }
+        let ready = self.reload.as_ref().is_some_and(|r| self.loaded.is_none() || r.is_finished());
+        if ready && let Some(reload) = self.reload.take() {
+            match reload.await {
+                Ok(entities) => self.entities = entities,
+                Err(e) => error!("Failed to load bookmarks: {}", e),
+            }
+            self.loaded = Some(Instant::now());
+        }
+        &self.entities
+    }
+}
+
+pub fn bookmark_lookup(haystack: &Vec<FileEntity>, query: &str, matcher: &mut Matcher) -> Vec<LauncherEntity> {
+    search_entity(haystack, query.to_string(), matcher)
+}
+
+/// `Open URL` result for a query which is a URL
+pub fn url_lookup(query: &str) -> Vec<LauncherEntity> {
+    let Some(url) = as_url(query) else {
+        return Vec::new();
+    };
+    let entity = ProviderEntity {
+        subtitle: Some(url.clone()),
+        ..url_entity("Open URL".to_string(), url)
+    };
+    vec![FileEntity::Provider(entity).into_launcher_entity()]
+}
+
+pub fn bookmark_entity(bookmark: Bookmark) -> ProviderEntity {
+    let match_text = [bookmark.title.as_str(), bookmark.url.as_str()].into_iter()
+        .chain(bookmark.tags.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let title = if bookmark.title.is_empty() { bookmark.url.clone() } else { bookmark.title };
+    ProviderEntity {
+        match_name: Utf32String::from(match_text),
+        subtitle: Some(bookmark.url.clone()),
+        ..url_entity(title, bookmark.url)
+    }
+}
+
+fn url_entity(title: String, url: String) -> ProviderEntity {
+    ProviderEntity {
+        dispatcher: Dispatcher::Hyprctl,
+        match_name: Utf32String::from(title.as_str()),
+        match_rank: None,
+        path: PathBuf::new(),
+        ui_name: title,
+        provider: PROVIDER_NAME.to_string(),
+        subtitle: None,
+        icon: Some("web-browser".to_string()),
+        action: Some(open_url_command(&url)),
+    }
+}
+
+//
+// sources
+//
+
+/// Read every configured source, skipping duplicate URLs
+pub fn load_bookmarks(config: &BookmarksConfig) -> Vec<Bookmark> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut bookmarks = Vec::new();
+
+    for file in &config.files {
+        let path = match &home {
+            Some(home) => expand_home(Path::new(file), home),
+            None => PathBuf::from(file),
+        };
+        match read_bookmark_file(&path) {
+            Ok(found) => bookmarks.extend(found),
+            Err(e) => error!("Failed to read bookmarks {}: {}", path.display(), e),
+        }
+    }
+    if let Some(home) = &home {
+        if config.chromium {
+            for path in chromium_bookmark_files(&home.join(".config")) {
+                match fs::read_to_string(&path) {
+                    Ok(json) => bookmarks.extend(parse_chromium(&json)),
+                    Err(e) => warn!("Failed to read {}: {}", path.display(), e),
+                }
+            }
+        }
+        if config.firefox {
+            for path in firefox_places(&home.join(".mozilla").join("firefox")) {
+                bookmarks.extend(read_firefox(&path));
+            }
+        }
+    }
+
+    let mut seen = HashSet::new();
+    bookmarks.retain(|b| seen.insert(b.url.clone()));
+    bookmarks
+}
+
+/// `.toml` files hold `[[bookmarks]]` tables, other files are read as markdown
+pub fn read_bookmark_file(path: &Path) -> Result<Vec<Bookmark>, String> {
+    let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    if path.extension().is_some_and(|ext| ext == "toml") {
+        parse_toml(&contents)
+    } else {
+        Ok(parse_markdown(&contents))
+    }
+}
+
+#[derive(Deserialize)]
+struct TomlBookmarks {
+    #[serde(default)]
+    bookmarks: Vec<TomlBookmark>,
+}
+#[derive(Deserialize)]
+struct TomlBookmark {
+    #[serde(default)]
+    title: String,
+    url: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+/// ```toml
+/// [[bookmarks]]
+/// title = "Hyprland wiki"
+/// url = "https://wiki.hypr.land"
+/// tags = ["docs"]
+/// ```
+pub fn parse_toml(contents: &str) -> Result<Vec<Bookmark>, String> {
+    let file: TomlBookmarks = toml::from_str(contents).map_err(|e| e.to_string())?;
+    Ok(file.bookmarks.into_iter()
+        .map(|b| Bookmark { title: b.title, url: b.url, tags: b.tags })
+        .collect())
+}
+
+/// `[title](url)` links, and lines holding only a URL (`- https://…`, `<https://…>`).
+/// Headings are used as tags for the links below them.
+pub fn parse_markdown(contents: &str) -> Vec<Bookmark> {
+    let mut bookmarks = Vec::new();
+    let mut heading: Option<String> = None;
+
+    for line in contents.lines().map(str::trim) {
+        if let Some(title) = line.strip_prefix('#') {
+            heading = Some(title.trim_start_matches('#').trim().to_string()).filter(|h| !h.is_empty());
+            continue;
+        }
+        let tags: Vec<String> = heading.iter().cloned().collect();
+
+        let links = markdown_links(line);
+        if !links.is_empty() {
+            bookmarks.extend(links.into_iter().map(|(title, url)| Bookmark { title, url, tags: tags.clone() }));
+            continue;
+        }
+        let bare = line.trim_start_matches(['-', '*', '+']).trim().trim_start_matches('<').trim_end_matches('>');
+        if bare.contains("://") && !bare.contains(char::is_whitespace) {
+            bookmarks.push(Bookmark { title: String::new(), url: bare.to_string(), tags });
+        }
+    }
+    bookmarks
+}
+
+fn markdown_links(line: &str) -> Vec<(String, String)> {
+    let mut links = Vec::new();
+    let mut rest = line;
+    while let Some(open) = rest.find('[') {
+        let after = &rest[open + 1..];
+        let Some(close) = after.find("](") else { break };
+        let title = &after[..close];
+        let target = &after[close + 2..];
+        let Some(end) = target.find(')') else { break };
+        let url = target[..end].trim();
+        if url.contains("://") {
+            links.push((title.trim().to_string(), url.to_string()));
+        }
+        rest = &target[end + 1..];
+    }
+    links
+}
+
+/// Chromium `Bookmarks` JSON, folder names become tags
+pub fn parse_chromium(json: &str) -> Vec<Bookmark> {
+    let Ok(root) = serde_json::from_str::<Value>(json) else {
+        return Vec::new();
+    };
+    let mut bookmarks = Vec::new();
+    if let Some(roots) = root.get("roots").and_then(Value::as_object) {
+        for node in roots.values() {
+            collect_chromium(node, &mut Vec::new(), &mut bookmarks);
+        }
+    }
+    bookmarks
+}
+
+fn collect_chromium(node: &Value, folders: &mut Vec<String>, bookmarks: &mut Vec<Bookmark>) {
+    let name = node.get("name").and_then(Value::as_str).unwrap_or("");
+    match node.get("type").and_then(Value::as_str) {
+        Some("url") => {
+            if let Some(url) = node.get("url").and_then(Value::as_str) {
+                bookmarks.push(Bookmark { title: name.to_string(), url: url.to_string(), tags: folders.clone() });
+            }
+        },
+        Some("folder") => {
+            folders.push(name.to_string());
+            for child in node.get("children").and_then(Value::as_array).into_iter().flatten() {
+                collect_chromium(child, folders, bookmarks);
+            }
+            folders.pop();
+        },
+        _ => {},
+    }
+}
+
+/// `Bookmarks` files of every profile of Chromium based browsers under `config_dir`
+fn chromium_bookmark_files(config_dir: &Path) -> Vec<PathBuf> {
+    CHROMIUM_DIRS.iter()
+        .flat_map(|browser| profile_dirs(&config_dir.join(browser)))
+        .filter(|dir| {
+            let name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            name == "Default" || name.starts_with("Profile ")
+        })
+        .map(|dir| dir.join("Bookmarks"))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+fn firefox_places(firefox_dir: &Path) -> Vec<PathBuf> {
+    profile_dirs(firefox_dir).into_iter()
+        .map(|dir| dir.join("places.sqlite"))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+fn profile_dirs(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir).into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+/// Query a copy of places.sqlite with the `sqlite3` command
+fn read_firefox(places: &Path) -> Vec<Bookmark> {
+    let copy_dir = std::env::temp_dir().join(format!("shunpo-places-{}", std::process::id()));
+    let copy = copy_dir.join("places.sqlite");
+    let copied = fs::create_dir_all(&copy_dir)
+        .and_then(|_| fs::copy(places, &copy))
+        .and_then(|_| {
+            // recent changes may still be in the write-ahead log
+            let wal = places.with_file_name("places.sqlite-wal");
+            if wal.is_file() { fs::copy(wal, copy_dir.join("places.sqlite-wal")).map(|_| ()) } else { Ok(()) }
+        });
+    if let Err(e) = copied {
+        warn!("Failed to copy {}: {}", places.display(), e);
+        let _ = fs::remove_dir_all(&copy_dir);
+        return Vec::new();
+    }
+
+    let output = Command::new("sqlite3")
+        .args(["-batch", "-separator", "\t"])
+        .arg(&copy)
+        .arg(FIREFOX_QUERY)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output();
+    let _ = fs::remove_dir_all(&copy_dir);
+
+    match output {
+        Ok(output) if output.status.success() => parse_sqlite_rows(&String::from_utf8_lossy(&output.stdout)),
+        Ok(output) => {
+            warn!("sqlite3 failed reading {}: {:?}", places.display(), output.status.code());
+            Vec::new()
+        },
+        Err(e) => {
+            warn!("Firefox bookmarks need sqlite3: {}", e);
+            Vec::new()
+        },
+    }
+}
+
+/// `title<TAB>url` rows
+pub fn parse_sqlite_rows(output: &str) -> Vec<Bookmark> {
+    output.lines()
+        .filter_map(|line| line.rsplit_once('\t'))
+        .filter(|(_, url)| !url.is_empty())
+        .map(|(title, url)| Bookmark { title: title.to_string(), url: url.to_string(), tags: Vec::new() })
+        .collect()
+}
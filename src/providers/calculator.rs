@@ -0,0 +1,535 @@
+use crate::search::entity_model::{LauncherEntity, VirtualAction, VirtualEntity};
+
+/// Queries starting with this are evaluated as expressions
+pub const CALC_PREFIX: &str = "=";
+
+#[derive(Debug, PartialEq)]
+pub enum CalcError {
+    UnexpectedEnd,
+    UnexpectedToken(String),
+    UnknownName(String),
+    UnknownUnit(String),
+    IncompatibleUnits(String, String),
+    WrongArgCount(String),
+    NotFinite,
+}
+impl std::fmt::Display for CalcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "incomplete expression"),
+            Self::UnexpectedToken(t) => write!(f, "unexpected '{}'", t),
+            Self::UnknownName(n) => write!(f, "unknown function or constant '{}'", n),
+            Self::UnknownUnit(u) => write!(f, "unknown unit '{}'", u),
+            Self::IncompatibleUnits(a, b) => write!(f, "can't convert {} to {}", a, b),
+            Self::WrongArgCount(n) => write!(f, "wrong number of arguments for {}", n),
+            Self::NotFinite => write!(f, "result is not a number"),
+        }
+    }
+}
+
+/// Calculator result rows for a query: `= <expression> [in hex|bin|oct]`, or `<value> <unit> in <unit>`.
+/// Empty when the query is neither.
+pub fn calc_lookup(query: &str) -> Vec<LauncherEntity> {
+    let query = query.trim();
+    let answer = match query.strip_prefix(CALC_PREFIX) {
+        Some(expr) if expr.trim().is_empty() => return Vec::new(),
+        Some(expr) => evaluate_query(expr).map(|(value, shown)| (value, format!("= {}", shown))),
+        // without the prefix only successful conversions are shown, the query may be meant for something else
+        None => match convert_query(query) {
+            Some(Ok((value, shown))) => Ok((value, format!("{} = {}", query_value(query), shown))),
+            _ => return Vec::new(),
+        },
+    };
+
+    let entity = match answer {
+        Ok((value, shown)) => VirtualEntity::with_action(shown, VirtualAction::CopyToClipboard(value)),
+        Err(e) => VirtualEntity::no_dispatch(format!("= {}", e)),
+    };
+    vec![LauncherEntity::from_virtual(&entity)]
+}
+
+/// Evaluate an expression with an optional radix or unit conversion suffix.
+/// Returns the value to copy and the text to show.
+pub fn evaluate_query(query: &str) -> Result<(String, String), CalcError> {
+    let query = query.trim();
+    if let Some((expr, target)) = split_conversion(query) {
+        if let Some(radix) = Radix::from_name(target) {
+            let value = evaluate(expr)?;
+            let text = radix.format(value)?;
+            return Ok((text.clone(), text));
+        }
+        if let Some(result) = convert_query(query) {
+            return result;
+        }
+    }
+    let value = format_number(evaluate(query)?)?;
+    Ok((value.clone(), value))
+}
+
+/// `<expression> <unit> in|to <unit>`, None when the query doesn't convert from a known unit
+pub fn convert_query(query: &str) -> Option<Result<(String, String), CalcError>> {
+    let (source, target) = split_conversion(query)?;
+    let (expr, from_unit) = split_value_unit(source)?;
+    let Some(to_unit) = find_unit(target) else {
+        return Some(Err(CalcError::UnknownUnit(target.to_string())));
+    };
+
+    Some(evaluate(expr).and_then(|value| {
+        let converted = convert(value, from_unit, to_unit)?;
+        let number = format_number(converted)?;
+        Ok((number.clone(), format!("{} {}", number, to_unit.symbol)))
+    }))
+}
+
+/// The part of a conversion before `in`/`to`, shown with the result
+fn query_value(query: &str) -> &str {
+    split_conversion(query).map(|(source, _)| source.trim()).unwrap_or(query)
+}
+
+fn split_conversion(query: &str) -> Option<(&str, &str)> {
+    [" in ", " to "].iter()
+        .filter_map(|sep| query.rfind(sep).map(|i| (i, sep.len())))
+        .max_by_key(|(i, _)| *i)
+        .map(|(i, len)| (&query[..i], query[i + len..].trim()))
+        .filter(|(source, target)| !source.trim().is_empty() && !target.is_empty())
+}
+
+/// `2 * 12 km` or `2 * 12km` -> (`2 * 12`, km)
+fn split_value_unit(source: &str) -> Option<(&str, &'static Unit)> {
+    let source = source.trim();
+    let last = source.rsplit(' ').next()?;
+    let word_start = source.len() - last.len();
+
+    let (expr, unit) = match find_unit(last) {
+        Some(unit) => (&source[..word_start], unit),
+        None => {
+            let idx = last.find(|c: char| c.is_alphabetic() || c == '°')?;
+            (&source[..word_start + idx], find_unit(&last[idx..])?)
+        },
+    };
+    if expr.trim().is_empty() { None } else { Some((expr, unit)) }
+}
+
+//
+// evaluation
+//
+
+/// Evaluate an arithmetic expression: `+ - * / % ^ !`, parentheses, `0x`/`0b`/`0o` literals,
+/// functions (`sqrt`, `sin`, `log`, `min`, ...) and constants (`pi`, `e`, `tau`).
+pub fn evaluate(expr: &str) -> Result<f64, CalcError> {
+    let tokens = tokenize(expr)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let value = parser.expression()?;
+    match parser.peek() {
+        None => Ok(value),
+        Some(token) => Err(CalcError::UnexpectedToken(token.to_string())),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(char),
+}
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Name(n) => write!(f, "{}", n),
+            Token::Op(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, CalcError> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        }
+        else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            let radix = match (c, chars.get(i + 1)) {
+                ('0', Some('x' | 'X')) => 16,
+                ('0', Some('b' | 'B')) => 2,
+                ('0', Some('o' | 'O')) => 8,
+                _ => 10,
+            };
+            if radix != 10 {
+                i += 2;
+                let digits_start = i;
+                while i < chars.len() && (chars[i].is_digit(radix) || chars[i] == '_') {
+                    i += 1;
+                }
+                let digits: String = chars[digits_start..i].iter().filter(|c| **c != '_').collect();
+                let value = i64::from_str_radix(&digits, radix)
+                    .map_err(|_| CalcError::UnexpectedToken(chars[start..i].iter().collect()))?;
+                tokens.push(Token::Number(value as f64));
+                continue;
+            }
+
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.' || chars[i] == '_') {
+                i += 1;
+            }
+            // exponent, e.g. 1.5e-3
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let mut j = i + 1;
+                if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+                    j += 1;
+                }
+                if j < chars.len() && chars[j].is_ascii_digit() {
+                    i = j;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().filter(|c| **c != '_').collect();
+            let value = text.parse::<f64>().map_err(|_| CalcError::UnexpectedToken(text.clone()))?;
+            tokens.push(Token::Number(value));
+        }
+        else if c.is_alphabetic() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Name(chars[start..i].iter().collect::<String>().to_lowercase()));
+        }
+        else if "+-*/%^!(),".contains(c) {
+            // `**` is an alias for `^`, `×` and `÷` are accepted too
+            if c == '*' && chars.get(i + 1) == Some(&'*') {
+                tokens.push(Token::Op('^'));
+                i += 2;
+                continue;
+            }
+            tokens.push(Token::Op(c));
+            i += 1;
+        }
+        else if c == '×' {
+            tokens.push(Token::Op('*'));
+            i += 1;
+        }
+        else if c == '÷' {
+            tokens.push(Token::Op('/'));
+            i += 1;
+        }
+        else {
+            return Err(CalcError::UnexpectedToken(c.to_string()));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent, lowest precedence first:
+/// `+ -`, `* / %`, unary `-`, `^` (right associative), `!`
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+    fn eat(&mut self, op: char) -> bool {
+        if self.peek() == Some(&Token::Op(op)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+    fn expect(&mut self, op: char) -> Result<(), CalcError> {
+        match self.next() {
+            Some(Token::Op(c)) if c == op => Ok(()),
+            Some(token) => Err(CalcError::UnexpectedToken(token.to_string())),
+            None => Err(CalcError::UnexpectedEnd),
+        }
+    }
+
+    fn expression(&mut self) -> Result<f64, CalcError> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+') { value += self.term()?; }
+            else if self.eat('-') { value -= self.term()?; }
+            else { return Ok(value); }
+        }
+    }
+    fn term(&mut self) -> Result<f64, CalcError> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat('*') { value *= self.unary()?; }
+            else if self.eat('/') { value /= self.unary()?; }
+            else if self.eat('%') { value %= self.unary()?; }
+            // implicit multiplication, e.g. 2pi or 3(4 + 1)
+            else if matches!(self.peek(), Some(Token::Name(_)) | Some(Token::Op('('))) { value *= self.unary()?; }
+            else { return Ok(value); }
+        }
+    }
+    fn unary(&mut self) -> Result<f64, CalcError> {
+        if self.eat('-') { return Ok(-self.unary()?); }
+        if self.eat('+') { return self.unary(); }
+        self.power()
+    }
+    fn power(&mut self) -> Result<f64, CalcError> {
+        let base = self.postfix()?;
+        if self.eat('^') {
+            // -2^2 is -(2^2), 2^-1 is 2^(-1)
+            return Ok(base.powf(self.unary()?));
+        }
+        Ok(base)
+    }
+    fn postfix(&mut self) -> Result<f64, CalcError> {
+        let mut value = self.primary()?;
+        while self.eat('!') {
+            value = factorial(value)?;
+        }
+        Ok(value)
+    }
+    fn primary(&mut self) -> Result<f64, CalcError> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(n),
+            Some(Token::Op('(')) => {
+                let value = self.expression()?;
+                self.expect(')')?;
+                Ok(value)
+            },
+            Some(Token::Name(name)) => {
+                if self.eat('(') {
+                    let mut args = Vec::new();
+                    if !self.eat(')') {
+                        loop {
+                            args.push(self.expression()?);
+                            if self.eat(')') { break; }
+                            self.expect(',')?;
+                        }
+                    }
+                    call_function(&name, &args)
+                }
+                else {
+                    constant(&name).ok_or(CalcError::UnknownName(name))
+                }
+            },
+            Some(token) => Err(CalcError::UnexpectedToken(token.to_string())),
+            None => Err(CalcError::UnexpectedEnd),
+        }
+    }
+}
+
+fn constant(name: &str) -> Option<f64> {
+    match name {
+        "pi" | "π" => Some(std::f64::consts::PI),
+        "tau" | "τ" => Some(std::f64::consts::TAU),
+        "e" => Some(std::f64::consts::E),
+        _ => None,
+    }
+}
+
+fn call_function(name: &str, args: &[f64]) -> Result<f64, CalcError> {
+    let one = |f: fn(f64) -> f64| match args {
+        [x] => Ok(f(*x)),
+        _ => Err(CalcError::WrongArgCount(name.to_string())),
+    };
+    match name {
+        "sqrt" => one(f64::sqrt),
+        "cbrt" => one(f64::cbrt),
+        "abs" => one(f64::abs),
+        "sin" => one(f64::sin),
+        "cos" => one(f64::cos),
+        "tan" => one(f64::tan),
+        "asin" => one(f64::asin),
+        "acos" => one(f64::acos),
+        "atan" => one(f64::atan),
+        "ln" => one(f64::ln),
+        "log" | "log10" => one(f64::log10),
+        "log2" => one(f64::log2),
+        "exp" => one(f64::exp),
+        "floor" => one(f64::floor),
+        "ceil" => one(f64::ceil),
+        "round" => one(f64::round),
+        "deg" => one(f64::to_degrees),
+        "rad" => one(f64::to_radians),
+        "pow" => match args {
+            [x, y] => Ok(x.powf(*y)),
+            _ => Err(CalcError::WrongArgCount(name.to_string())),
+        },
+        "min" | "max" if !args.is_empty() => {
+            let pick = if name == "min" { f64::min } else { f64::max };
+            Ok(args.iter().copied().fold(args[0], pick))
+        },
+        "min" | "max" => Err(CalcError::WrongArgCount(name.to_string())),
+        _ => Err(CalcError::UnknownName(name.to_string())),
+    }
+}
+
+fn factorial(value: f64) -> Result<f64, CalcError> {
+    if value < 0.0 || value.fract() != 0.0 || value > 170.0 {
+        return Err(CalcError::NotFinite);
+    }
+    Ok((1..=value as u64).fold(1.0, |acc, n| acc * n as f64))
+}
+
+//
+// formatting
+//
+
+enum Radix {
+    Hex,
+    Bin,
+    Oct,
+    Dec,
+}
+impl Radix {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "hex" | "hexadecimal" => Some(Radix::Hex),
+            "bin" | "binary" => Some(Radix::Bin),
+            "oct" | "octal" => Some(Radix::Oct),
+            "dec" | "decimal" => Some(Radix::Dec),
+            _ => None,
+        }
+    }
+    fn format(&self, value: f64) -> Result<String, CalcError> {
+        if !value.is_finite() {
+            return Err(CalcError::NotFinite);
+        }
+        let int = value.trunc() as i64;
+        let (sign, abs) = if int < 0 { ("-", int.unsigned_abs()) } else { ("", int as u64) };
+        Ok(match self {
+            Radix::Hex => format!("{}0x{:x}", sign, abs),
+            Radix::Bin => format!("{}0b{:b}", sign, abs),
+            Radix::Oct => format!("{}0o{:o}", sign, abs),
+            Radix::Dec => return format_number(value),
+        })
+    }
+}
+
+/// Integers without a fraction, others with up to 10 decimals, very large or small numbers in scientific notation
+pub fn format_number(value: f64) -> Result<String, CalcError> {
+    if !value.is_finite() {
+        return Err(CalcError::NotFinite);
+    }
+    if value == 0.0 {
+        return Ok("0".to_string());
+    }
+    let abs = value.abs();
+    if !(1e-6..1e15).contains(&abs) {
+        return Ok(format!("{:e}", value));
+    }
+    if value.fract() == 0.0 {
+        return Ok(format!("{}", value as i64));
+    }
+    let text = format!("{:.10}", value);
+    Ok(text.trim_end_matches('0').trim_end_matches('.').to_string())
+}
+
+//
+// units
+//
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Dimension {
+    Length,
+    Mass,
+    Time,
+    Volume,
+    Data,
+    Speed,
+    Temperature,
+}
+
+#[derive(Debug)]
+struct Unit {
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    dimension: Dimension,
+    /// Size in the dimension's base unit; for temperatures, the scale relative to kelvin
+    factor: f64,
+    /// Added after scaling to get the base unit, only for temperatures
+    offset: f64,
+}
+
+const fn unit(symbol: &'static str, aliases: &'static [&'static str], dimension: Dimension, factor: f64) -> Unit {
+    Unit { symbol, aliases, dimension, factor, offset: 0.0 }
+}
+
+const UNITS: &[Unit] = &[
+    // length, in metres
+    unit("mm", &["millimeter", "millimeters", "millimetre", "millimetres"], Dimension::Length, 0.001),
+    unit("cm", &["centimeter", "centimeters", "centimetre", "centimetres"], Dimension::Length, 0.01),
+    unit("m", &["meter", "meters", "metre", "metres"], Dimension::Length, 1.0),
+    unit("km", &["kilometer", "kilometers", "kilometre", "kilometres"], Dimension::Length, 1000.0),
+    unit("in", &["inch", "inches"], Dimension::Length, 0.0254),
+    unit("ft", &["foot", "feet"], Dimension::Length, 0.3048),
+    unit("yd", &["yard", "yards"], Dimension::Length, 0.9144),
+    unit("mi", &["mile", "miles"], Dimension::Length, 1609.344),
+    unit("nmi", &["nauticalmile", "nauticalmiles"], Dimension::Length, 1852.0),
+    // mass, in grams
+    unit("mg", &["milligram", "milligrams"], Dimension::Mass, 0.001),
+    unit("g", &["gram", "grams"], Dimension::Mass, 1.0),
+    unit("kg", &["kilogram", "kilograms", "kilo", "kilos"], Dimension::Mass, 1000.0),
+    unit("t", &["tonne", "tonnes", "ton", "tons"], Dimension::Mass, 1_000_000.0),
+    unit("oz", &["ounce", "ounces"], Dimension::Mass, 28.349523125),
+    unit("lb", &["lbs", "pound", "pounds"], Dimension::Mass, 453.59237),
+    unit("st", &["stone", "stones"], Dimension::Mass, 6350.29318),
+    // time, in seconds
+    unit("ms", &["millisecond", "milliseconds"], Dimension::Time, 0.001),
+    unit("s", &["sec", "secs", "second", "seconds"], Dimension::Time, 1.0),
+    unit("min", &["mins", "minute", "minutes"], Dimension::Time, 60.0),
+    unit("h", &["hr", "hrs", "hour", "hours"], Dimension::Time, 3600.0),
+    unit("d", &["day", "days"], Dimension::Time, 86_400.0),
+    unit("wk", &["week", "weeks"], Dimension::Time, 604_800.0),
+    unit("yr", &["year", "years"], Dimension::Time, 31_557_600.0),
+    // volume, in litres
+    unit("ml", &["milliliter", "milliliters", "millilitre", "millilitres"], Dimension::Volume, 0.001),
+    unit("l", &["liter", "liters", "litre", "litres"], Dimension::Volume, 1.0),
+    unit("floz", &["fluidounce", "fluidounces"], Dimension::Volume, 0.0295735295625),
+    unit("cup", &["cups"], Dimension::Volume, 0.2365882365),
+    unit("pt", &["pint", "pints"], Dimension::Volume, 0.473176473),
+    unit("gal", &["gallon", "gallons"], Dimension::Volume, 3.785411784),
+    // data, in bytes
+    unit("bit", &["bits"], Dimension::Data, 0.125),
+    unit("B", &["byte", "bytes"], Dimension::Data, 1.0),
+    unit("KB", &["kilobyte", "kilobytes"], Dimension::Data, 1e3),
+    unit("MB", &["megabyte", "megabytes"], Dimension::Data, 1e6),
+    unit("GB", &["gigabyte", "gigabytes"], Dimension::Data, 1e9),
+    unit("TB", &["terabyte", "terabytes"], Dimension::Data, 1e12),
+    unit("KiB", &["kibibyte", "kibibytes"], Dimension::Data, 1024.0),
+    unit("MiB", &["mebibyte", "mebibytes"], Dimension::Data, 1_048_576.0),
+    unit("GiB", &["gibibyte", "gibibytes"], Dimension::Data, 1_073_741_824.0),
+    unit("TiB", &["tebibyte", "tebibytes"], Dimension::Data, 1_099_511_627_776.0),
+    // speed, in metres per second
+    unit("m/s", &["mps"], Dimension::Speed, 1.0),
+    unit("km/h", &["kmh", "kph"], Dimension::Speed, 1000.0 / 3600.0),
+    unit("mph", &["mi/h"], Dimension::Speed, 1609.344 / 3600.0),
+    unit("kn", &["knot", "knots"], Dimension::Speed, 1852.0 / 3600.0),
+    // temperature, in kelvin
+    Unit { symbol: "K", aliases: &["kelvin"], dimension: Dimension::Temperature, factor: 1.0, offset: 0.0 },
+    Unit { symbol: "°C", aliases: &["c", "celsius", "degc"], dimension: Dimension::Temperature, factor: 1.0, offset: 273.15 },
+    Unit { symbol: "°F", aliases: &["f", "fahrenheit", "degf"], dimension: Dimension::Temperature, factor: 5.0 / 9.0, offset: 459.67 * 5.0 / 9.0 },
+];
+
+/// Symbols are case sensitive (`mb` is not `MB`) unless nothing matches, aliases never are
+fn find_unit(name: &str) -> Option<&'static Unit> {
+    let lower = name.to_lowercase();
+    UNITS.iter().find(|u| u.symbol == name)
+        .or_else(|| UNITS.iter().find(|u| u.aliases.contains(&lower.as_str())))
+        .or_else(|| UNITS.iter().find(|u| u.symbol.to_lowercase() == lower))
+}
+
+fn convert(value: f64, from: &Unit, to: &Unit) -> Result<f64, CalcError> {
+    if from.dimension != to.dimension {
+        return Err(CalcError::IncompatibleUnits(from.symbol.to_string(), to.symbol.to_string()));
+    }
+    let base = value * from.factor + from.offset;
+    Ok((base - to.offset) / to.factor)
+}
@@ -0,0 +1,178 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use log::{error, warn};
+use nucleo::{Matcher, Utf32String};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::config::ClipboardConfig,
+    search::{
+        entity_model::{Export, FileEntity, LauncherEntity, VirtualAction, VirtualEntity},
+        matcher_helpers::search_entity,
+    },
+    system::{clipboard::watch_clipboard, paths::shunpo_data_dir},
+};
+
+/// Queries starting with this search the clipboard history
+pub const CLIP_PREFIX: &str = "c ";
+const HISTORY_FILE: &str = "clipboard_history.json";
+/// Characters of an entry shown in results
+const PREVIEW_CHARS: usize = 80;
+/// Entries listed for an empty query, most recent first
+const RECENT_RESULTS: usize = 50;
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClipEntry {
+    pub text: String,
+    /// Unix time in seconds
+    pub copied_at: u64,
+}
+
+/// Copied texts, most recent first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ClipboardHistory {
+    entries: Vec<ClipEntry>,
+}
+/// History shared by the clipboard watcher, search and coordinator
+pub type SharedHistory = Arc<Mutex<ClipboardHistory>>;
+
+impl ClipboardHistory {
+    pub fn entries(&self) -> &[ClipEntry] {
+        &self.entries
+    }
+    /// Add a copy, moving it to the front if it was copied before
+    pub fn add(&mut self, text: String, now: u64, limits: &ClipboardConfig) {
+        if text.len() > limits.max_entry_bytes {
+            return;
+        }
+        self.entries.retain(|e| e.text != text);
+        self.entries.insert(0, ClipEntry { text, copied_at: now });
+        self.prune(now, limits);
+    }
+    pub fn remove(&mut self, text: &str) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|e| e.text != text);
+        self.entries.len() != len
+    }
+    /// Drop entries beyond the size and age limits
+    pub fn prune(&mut self, now: u64, limits: &ClipboardConfig) {
+        if limits.max_age_days > 0 {
+            let max_age = limits.max_age_days * SECONDS_PER_DAY;
+            self.entries.retain(|e| now.saturating_sub(e.copied_at) <= max_age);
+        }
+        self.entries.truncate(limits.max_entries);
+    }
+
+    pub fn load(path: &Path) -> Self {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable clipboard history {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+    /// Written readable by the user only, copies may be sensitive
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = serde_json::to_string(self)?;
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+        file.write_all(contents.as_bytes())
+    }
+}
+
+pub fn history_path() -> Option<PathBuf> {
+    shunpo_data_dir().map(|dir| dir.join(HISTORY_FILE))
+}
+
+/// Load the history and record new copies into it. A disabled history stays empty,
+/// a file kept from when it was enabled is neither read nor searched.
+pub fn start_clipboard_history(config: &ClipboardConfig) -> SharedHistory {
+    if !config.enabled {
+        return SharedHistory::default();
+    }
+    let Some(path) = history_path() else {
+        error!("Clipboard history disabled, no data directory (HOME is unset).");
+        return SharedHistory::default();
+    };
+    let mut history = ClipboardHistory::load(&path);
+    history.prune(unix_now(), config);
+    let history = Arc::new(Mutex::new(history));
+
+    let limits = config.clone();
+    let shared = history.clone();
+    watch_clipboard(config.excluded_mime_types.clone(), move |text| {
+        let Ok(mut history) = shared.lock() else { return };
+        history.add(text, unix_now(), &limits);
+        if let Err(e) = history.save(&path) {
+            error!("Failed to save clipboard history: {}", e);
+        }
+    });
+    history
+}
+
+/// Remove an entry and save the history
+pub fn delete_clip(history: &SharedHistory, text: &str) {
+    let Ok(mut history) = history.lock() else { return };
+    if !history.remove(text) {
+        return;
+    }
+    if let Some(path) = history_path()
+        && let Err(e) = history.save(&path)
+    {
+        error!("Failed to save clipboard history: {}", e);
+    }
+}
+
+/// Entries matching `query`, the most recent ones for an empty query
+pub fn clip_lookup(history: &ClipboardHistory, query: &str, matcher: &mut Matcher) -> Vec<LauncherEntity> {
+    let entities: Vec<FileEntity> = history.entries().iter()
+        .map(|e| FileEntity::Virtual(clip_entity(&e.text)))
+        .collect();
+
+    if query.trim().is_empty() {
+        return entities.iter()
+            .take(RECENT_RESULTS)
+            .map(|e| e.into_launcher_entity())
+            .collect();
+    }
+    search_entity(&entities, query.to_string(), matcher)
+}
+
+/// Restores `text` to the clipboard when chosen
+pub fn clip_entity(text: &str) -> VirtualEntity {
+    VirtualEntity {
+        match_name: Utf32String::from(text),
+        ..VirtualEntity::with_action(preview(text), VirtualAction::RestoreClip(text.to_string()))
+    }
+}
+
+/// First line, shortened, with a marker for further lines
+pub fn preview(text: &str) -> String {
+    let mut lines = text.trim().lines();
+    let first = lines.next().unwrap_or("").trim();
+    let mut preview: String = first.chars().take(PREVIEW_CHARS).collect();
+    if first.chars().count() > PREVIEW_CHARS || lines.next().is_some() {
+        preview.push('…');
+    }
+    preview
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
@@ -0,0 +1,59 @@
+use nucleo::{Matcher, Utf32String};
+
+use crate::{
+    config::config::EmojiAction,
+    search::{
+        entity_model::{FileEntity, LauncherEntity, VirtualAction, VirtualEntity},
+        matcher_helpers::search_entity,
+    },
+};
+
+/// Queries starting with this search emoji and other characters
+pub const EMOJI_PREFIX: &str = ":e ";
+/// `glyph<TAB>name<TAB>keywords` per line, `#` lines are comments
+const EMOJI_TABLE: &str = include_str!("emoji.tsv");
+
+#[derive(Debug, PartialEq)]
+pub struct Emoji {
+    pub glyph: &'static str,
+    /// Unicode character name, lowercase
+    pub name: &'static str,
+    /// Extra search terms, space separated
+    pub keywords: &'static str,
+}
+
+pub fn emoji_table() -> Vec<Emoji> {
+    EMOJI_TABLE.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let glyph = fields.next().filter(|g| !g.is_empty())?;
+            let name = fields.next()?;
+            Some(Emoji { glyph, name, keywords: fields.next().unwrap_or("") })
+        })
+        .collect()
+}
+
+/// Searchable entities for every character, copying or typing it when chosen
+pub fn emoji_entities(action: EmojiAction) -> Vec<FileEntity> {
+    emoji_table().into_iter()
+        .map(|emoji| {
+            let glyph = emoji.glyph.to_string();
+            let action = match action {
+                EmojiAction::Copy => VirtualAction::CopyToClipboard(glyph),
+                EmojiAction::Type => VirtualAction::TypeText(glyph),
+            };
+            FileEntity::Virtual(VirtualEntity {
+                match_name: Utf32String::from(format!("{} {}", emoji.name, emoji.keywords).trim()),
+                ..VirtualEntity::with_action(format!("{}   {}", emoji.glyph, emoji.name), action)
+            })
+        })
+        .collect()
+}
+
+pub fn emoji_lookup(haystack: &Vec<FileEntity>, query: &str, matcher: &mut Matcher) -> Vec<LauncherEntity> {
+    if query.trim().is_empty() {
+        return Vec::new();
+    }
+    search_entity(haystack, query.to_string(), matcher)
+}
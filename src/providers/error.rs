@@ -0,0 +1,18 @@
+pub enum ProviderError {
+    Spawn(std::io::Error),
+    TimedOut,
+    /// A newer query arrived first
+    Cancelled,
+    Exited(Option<i32>),
+}
+impl std::fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Spawn(e) => write!(f, "failed to start: {}", e),
+            Self::TimedOut => write!(f, "timed out"),
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::Exited(Some(code)) => write!(f, "exited with status {}", code),
+            Self::Exited(None) => write!(f, "killed by signal"),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    io::Read,
+    os::unix::process::CommandExt,
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    sync::{atomic::{AtomicBool, Ordering}, mpsc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::warn;
+use nucleo::Utf32String;
+use serde::Deserialize;
+
+use crate::{
+    config::config::ProviderConfig,
+    providers::error::ProviderError,
+    search::entity_model::{Dispatcher, Export, FileEntity, LauncherEntity, ProviderEntity},
+    system::{launch::{signal_group, SIGKILL}, launch_env::LaunchEnv},
+};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_millis(1000);
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// Output still arriving after the provider exited is cut off after this
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(200);
+/// Results shown per query, further lines are ignored
+const MAX_RESULTS: usize = 50;
+
+/// One result, a JSON object per line of the provider's output:
+///
+/// ```text
+/// {"title": "shunpo", "subtitle": "Hyprland launcher", "icon": "folder", "action": "xdg-open https://…"}
+/// ```
+///
+/// Only `title` is required. Results without an `action` are informational.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct ProviderItem {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub icon: Option<String>,
+    pub action: Option<String>,
+}
+
+/// Provider whose prefix starts the query, and the query without it
+pub fn match_provider<'a>(providers: &'a [ProviderConfig], query: &'a str) -> Option<(&'a ProviderConfig, &'a str)> {
+    providers.iter()
+        .filter(|p| !p.prefix.is_empty())
+        .find_map(|p| query.strip_prefix(p.prefix.as_str()).map(|rest| (p, rest)))
+}
+
+/// Blocking, runs the provider until it exits, times out or `cancel` is set.
+pub fn provider_lookup(provider: &ProviderConfig, query: &str, cancel: &AtomicBool) -> Result<Vec<LauncherEntity>, ProviderError> {
+    let timeout = provider.timeout_ms.map(Duration::from_millis).unwrap_or(DEFAULT_TIMEOUT);
+    let output = run_provider(&provider.command, query, timeout, cancel)?;
+
+    Ok(parse_items(&provider.name, &output)
+        .into_iter()
+        .map(|item| provider_entity(&provider.name, item).into_launcher_entity())
+        .collect())
+}
+
+/// Run `command` with the query as its last argument, returning stdout.
+/// The provider runs in its own process group, which is killed on timeout or cancel.
+pub fn run_provider(command: &str, query: &str, timeout: Duration, cancel: &AtomicBool) -> Result<String, ProviderError> {
+    let mut child_command = Command::new("sh");
+    LaunchEnv::original().apply(&mut child_command);
+    let mut child = child_command
+        .arg("-c")
+        .arg(format!("{} \"$@\"", command))
+        .arg("sh")
+        .arg(query)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+        .map_err(ProviderError::Spawn)?;
+
+    // read on a thread so a full pipe can't stall the provider
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let (chunk_tx, chunk_rx) = mpsc::channel::<Vec<u8>>();
+    thread::spawn(move || {
+        let mut buf = [0; 8192];
+        while let Ok(read) = stdout.read(&mut buf) {
+            if read == 0 || chunk_tx.send(buf[..read].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let deadline = Instant::now() + timeout;
+    let mut output = Vec::new();
+    let status = loop {
+        output.extend(chunk_rx.try_iter().flatten());
+        match child.try_wait().map_err(ProviderError::Spawn)? {
+            Some(status) => break status,
+            None if cancel.load(Ordering::Relaxed) => {
+                stop(&mut child);
+                return Err(ProviderError::Cancelled);
+            },
+            None if Instant::now() >= deadline => {
+                stop(&mut child);
+                return Err(ProviderError::TimedOut);
+            },
+            None => thread::sleep(POLL_INTERVAL),
+        }
+    };
+    if !status.success() {
+        return Err(ProviderError::Exited(status.code()));
+    }
+
+    // processes the provider started may keep stdout open, they aren't waited for
+    let drain_deadline = Instant::now() + DRAIN_TIMEOUT;
+    while let Some(left) = drain_deadline.checked_duration_since(Instant::now()) {
+        match chunk_rx.recv_timeout(left) {
+            Ok(chunk) => output.extend(chunk),
+            Err(_) => break,
+        }
+    }
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+fn stop(child: &mut Child) {
+    signal_group(child, SIGKILL);
+    let _ = child.wait();
+}
+
+/// Parse JSON lines, skipping blank and malformed ones
+pub fn parse_items(provider: &str, output: &str) -> Vec<ProviderItem> {
+    output.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| match serde_json::from_str::<ProviderItem>(line) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                warn!("Provider {}: skipping malformed result: {}", provider, e);
+                None
+            },
+        })
+        .take(MAX_RESULTS)
+        .collect()
+}
+
+pub fn provider_entity(provider: &str, item: ProviderItem) -> FileEntity {
+    let action = item.action.filter(|a| !a.trim().is_empty());
+    let dispatcher = if action.is_some() { Dispatcher::Hyprctl } else { Dispatcher::Virtual };
+
+    FileEntity::Provider(ProviderEntity {
+        dispatcher,
+        match_name: Utf32String::from(item.title.as_str()),
+        match_rank: None,
+        path: PathBuf::new(),
+        ui_name: item.title,
+        provider: provider.to_string(),
+        subtitle: item.subtitle,
+        icon: item.icon,
+        action,
+    })
+}
@@ -0,0 +1,10 @@
+pub mod bookmarks;
+pub mod calculator;
+pub mod clipboard;
+pub mod emoji;
+pub mod error;
+pub mod external;
+pub mod recent_files;
+pub mod ssh;
+pub mod url;
+pub mod web_search;
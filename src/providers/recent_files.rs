@@ -0,0 +1,320 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use chrono::{DateTime, Local, Utc};
+use log::{error, warn};
+use nucleo::{Matcher, Utf32String};
+use tokio::task::JoinHandle;
+
+use crate::{
+    providers::url::open_url_command,
+    search::{
+        entity_model::{Dispatcher, Export, FileEntity, LauncherEntity, ProviderEntity},
+        matcher_helpers::search_entity,
+    },
+    system::{launch::shell_quote, paths::data_home},
+};
+
+/// Queries starting with this search recently used files
+pub const RECENT_PREFIX: &str = "r ";
+const RECENT_FILE: &str = "recently-used.xbel";
+/// Files listed for an empty query, most recent first
+const RECENT_RESULTS: usize = 50;
+const PROVIDER_NAME: &str = "Recent files";
+const BOOKMARK_TAG: &str = "<bookmark ";
+const APPLICATION_TAG: &str = "<bookmark:application ";
+const MIME_TYPE_TAG: &str = "<mime:mime-type ";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecentFile {
+    pub path: PathBuf,
+    pub uri: String,
+    pub mime_type: Option<String>,
+    /// Last time the file was added, modified or visited
+    pub used_at: DateTime<Utc>,
+    /// The application which used the file last
+    pub app: Option<RecentApp>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecentApp {
+    pub name: String,
+    /// Command line with `%u`/`%f` for the file, as recorded by the app
+    pub exec: String,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+impl RecentFile {
+    pub fn file_name(&self) -> String {
+        self.path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.to_string_lossy().to_string())
+    }
+
+    /// The recorded application's command, or `xdg-open`
+    pub fn command(&self) -> String {
+        let exec = self.app.as_ref().map(|a| a.exec.trim()).filter(|e| !e.is_empty());
+        let Some(exec) = exec else {
+            return open_url_command(&self.path.to_string_lossy());
+        };
+        expand_field_codes(exec, &self.uri, &self.path.to_string_lossy())
+    }
+}
+
+/// Replace the field codes of a desktop entry command in one pass: `%u`/`%U` with the quoted
+/// `uri`, `%f`/`%F` with the quoted `path` and `%%` with `%`. Other codes, like `%i`, `%c` and `%k`,
+/// are dropped. The path is appended when the command has no file code.
+pub fn expand_field_codes(exec: &str, uri: &str, path: &str) -> String {
+    let mut command = String::with_capacity(exec.len());
+    let mut has_file = false;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            command.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('u' | 'U') => {
+                command.push_str(&shell_quote(uri));
+                has_file = true;
+            },
+            Some('f' | 'F') => {
+                command.push_str(&shell_quote(path));
+                has_file = true;
+            },
+            Some('%') => command.push('%'),
+            _ => {},
+        }
+    }
+    let command = command.trim_end();
+    if has_file { command.to_string() } else { format!("{} {}", command, shell_quote(path)) }
+}
+
+/// Recently used files, read on a blocking thread again when the file changes
+#[derive(Default)]
+pub struct RecentFileStore {
+    entities: Vec<FileEntity>,
+    modified: Option<SystemTime>,
+    loaded: bool,
+    reload: Option<JoinHandle<Vec<FileEntity>>>,
+}
+impl RecentFileStore {
+    /// The first load is waited for, older files are searched while a changed list is read.
+    pub async fn entities(&mut self) -> &Vec<FileEntity> {
+        if self.reload.is_none() && let Some(path) = recent_files_path() {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            if modified.is_some() && modified != self.modified {
+                self.modified = modified;
+                self.reload = Some(tokio::task::spawn_blocking(move || {
+                    load_recent_files(&path).iter()
+                        .map(|f| FileEntity::Provider(recent_entity(f)))
+                        .collect()
+                }));
+            }
+        }
+        let ready = self.reload.as_ref().is_some_and(|r| !self.loaded || r.is_finished());
+        if ready && let Some(reload) = self.reload.take() {
+            match reload.await {
+                Ok(entities) => self.entities = entities,
+                Err(e) => error!("Failed to load recent files: {}", e),
+            }
+            self.loaded = true;
+        }
+        &self.entities
+    }
+}
+
+pub fn recent_files_path() -> Option<PathBuf> {
+    data_home().map(|dir| dir.join(RECENT_FILE))
+}
+
+/// Files matching `query` by name or path, the most recent ones for an empty query
+pub fn recent_lookup(haystack: &Vec<FileEntity>, query: &str, matcher: &mut Matcher) -> Vec<LauncherEntity> {
+    if query.trim().is_empty() {
+        return haystack.iter()
+            .take(RECENT_RESULTS)
+            .map(|e| e.into_launcher_entity())
+            .collect();
+    }
+    search_entity(haystack, query.to_string(), matcher)
+}
+
+pub fn recent_entity(file: &RecentFile) -> ProviderEntity {
+    let path = file.path.to_string_lossy().to_string();
+    let used_at = file.used_at.with_timezone(&Local).format("%Y-%m-%d %H:%M");
+    let subtitle = match &file.app {
+        Some(app) => format!("{}  ·  {}  ·  {}", path, app.name, used_at),
+        None => format!("{}  ·  {}", path, used_at),
+    };
+    ProviderEntity {
+        dispatcher: Dispatcher::Hyprctl,
+        match_name: Utf32String::from(format!("{} {}", file.file_name(), path)),
+        match_rank: None,
+        path: file.path.clone(),
+        ui_name: file.file_name(),
+        provider: PROVIDER_NAME.to_string(),
+        subtitle: Some(subtitle),
+        icon: Some(mime_icon(file.mime_type.as_deref())),
+        action: Some(file.command()),
+    }
+}
+
+/// Generic icon of the mime type, e.g. `application-pdf` or `image-x-generic`
+fn mime_icon(mime_type: Option<&str>) -> String {
+    match mime_type.and_then(|m| m.split_once('/')) {
+        Some((kind @ ("image" | "audio" | "video" | "text"), _)) => format!("{}-x-generic", kind),
+        Some((kind, subtype)) => format!("{}-{}", kind, subtype),
+        None => "text-x-generic".to_string(),
+    }
+}
+
+/// Existing local files, most recent first
+pub fn load_recent_files(path: &Path) -> Vec<RecentFile> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            warn!("Failed to read {}: {}", path.display(), e);
+            return Vec::new();
+        },
+    };
+    let mut files: Vec<RecentFile> = parse_xbel(&contents).into_iter()
+        .filter(|f| f.path.exists())
+        .collect();
+    files.sort_by_key(|f| Reverse(f.used_at));
+    files
+}
+
+//
+// xbel
+//
+
+/// `<bookmark>` elements with `file://` URIs, in file order
+pub fn parse_xbel(contents: &str) -> Vec<RecentFile> {
+    let mut files = Vec::new();
+    for chunk in contents.split(BOOKMARK_TAG).skip(1) {
+        let element = chunk.split("</bookmark>").next().unwrap_or_default();
+        let tag = element.split('>').next().unwrap_or_default();
+        let Some(uri) = attribute(tag, "href") else { continue };
+        let Some(path) = file_uri_path(&uri) else { continue };
+
+        let app = last_app(element);
+        let used_at = ["added", "modified", "visited"].into_iter()
+            .filter_map(|name| attribute(tag, name).and_then(|t| parse_time(&t)))
+            .chain(app.as_ref().and_then(|a| a.used_at))
+            .max();
+        let Some(used_at) = used_at else { continue };
+
+        let mime_type = element.split(MIME_TYPE_TAG).nth(1)
+            .and_then(|chunk| attribute(chunk.split('>').next().unwrap_or_default(), "type"));
+        files.push(RecentFile { path, uri, mime_type, used_at, app });
+    }
+    files
+}
+
+/// The application which recorded the latest use
+fn last_app(element: &str) -> Option<RecentApp> {
+    element.split(APPLICATION_TAG).skip(1)
+        .map(|chunk| {
+            let tag = chunk.split('>').next().unwrap_or_default();
+            let exec = attribute(tag, "exec").unwrap_or_default();
+            // exec is written quoted, e.g. `'evince %u'`
+            let exec = exec.strip_prefix('\'').and_then(|e| e.strip_suffix('\'')).unwrap_or(&exec).to_string();
+            RecentApp {
+                name: attribute(tag, "name").unwrap_or_default(),
+                exec,
+                used_at: attribute(tag, "modified").and_then(|t| parse_time(&t)),
+            }
+        })
+        .max_by_key(|a| a.used_at)
+}
+
+/// Unescaped value of the attribute `name` of a tag
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let mut rest = tag;
+    loop {
+        let at = rest.find(name)?;
+        let before = rest[..at].chars().last();
+        let after = &rest[at + name.len()..];
+        if before.is_none_or(char::is_whitespace)
+            && let Some(value) = after.trim_start().strip_prefix('=')
+        {
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|q| *q == '"' || *q == '\'')?;
+            let end = value[1..].find(quote)?;
+            return Some(unescape(&value[1..end + 1]));
+        }
+        rest = after;
+    }
+}
+
+/// XML entities and character references
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(at) = rest.find('&') {
+        out.push_str(&rest[..at]);
+        rest = &rest[at..];
+        let Some(end) = rest.find(';') else { break };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity.strip_prefix("#x").map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            },
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Local path of a `file://` URI, percent-decoded
+pub fn file_uri_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    // `file://localhost/path` names the local host too
+    let path = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !path.starts_with('/') {
+        return None;
+    }
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            },
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            },
+        }
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&decoded).to_string()))
+}
+
+fn parse_time(text: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(text).ok().map(|t| t.with_timezone(&Utc))
+}
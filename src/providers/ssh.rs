@@ -0,0 +1,295 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use log::{error, warn};
+use nucleo::{Matcher, Utf32String};
+use tokio::task::JoinHandle;
+
+use crate::{
+    config::config::SshConfig,
+    search::{
+        entity_model::{Dispatcher, Export, FileEntity, LauncherEntity, ProviderEntity},
+        launch_options::expand_home,
+        matcher_helpers::search_entity,
+    },
+    system::launch::shell_quote,
+};
+
+/// Queries starting with this search SSH hosts
+pub const SSH_PREFIX: &str = "ssh ";
+/// Hosts are read again on the next search after this long
+const RELOAD_AFTER: Duration = Duration::from_secs(60);
+/// Hosts listed for an empty query, in config order
+const LISTED_HOSTS: usize = 50;
+/// Same limit as ssh, guards against include loops
+const MAX_INCLUDE_DEPTH: usize = 16;
+const PROVIDER_NAME: &str = "SSH";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SshHost {
+    /// Host alias, or host name from known_hosts
+    pub name: String,
+    pub hostname: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    /// Only known_hosts entries need the port on the command line, config hosts bring their own
+    pub from_config: bool,
+}
+impl SshHost {
+    fn named(name: &str, from_config: bool) -> Self {
+        SshHost { name: name.to_string(), from_config, ..Default::default() }
+    }
+
+    /// `ssh <host>`, run in the terminal
+    pub fn command(&self) -> String {
+        match self.port.filter(|_| !self.from_config) {
+            Some(port) => format!("ssh -p {} {}", port, shell_quote(&self.name)),
+            None => format!("ssh {}", shell_quote(&self.name)),
+        }
+    }
+
+    /// `user@hostname:port`, for the parts which are known
+    pub fn target(&self) -> String {
+        let mut target = String::new();
+        if let Some(user) = &self.user {
+            target.push_str(user);
+            target.push('@');
+        }
+        target.push_str(self.hostname.as_deref().unwrap_or(&self.name));
+        if let Some(port) = self.port {
+            target.push_str(&format!(":{}", port));
+        }
+        target
+    }
+}
+
+/// Hosts from the ssh config and known_hosts, read on a blocking thread and reloaded when stale
+#[derive(Default)]
+pub struct SshHostStore {
+    entities: Vec<FileEntity>,
+    loaded: Option<Instant>,
+    reload: Option<JoinHandle<Vec<FileEntity>>>,
+}
+impl SshHostStore {
+    /// The first load is waited for, stale hosts are searched while they are read again.
+    pub async fn entities(&mut self, config: &SshConfig) -> &Vec<FileEntity> {
+        if self.reload.is_none() && self.loaded.is_none_or(|at| at.elapsed() >= RELOAD_AFTER) {
+            let config = config.clone();
+            self.reload = Some(tokio::task::spawn_blocking(move || {
+                load_hosts(&config).iter()
+                    .map(|h| FileEntity::Provider(ssh_entity(h)))
+                    .collect()
+            }));
+        }
+        let ready = self.reload.as_ref().is_some_and(|r| self.loaded.is_none() || r.is_finished());
+        if ready && let Some(reload) = self.reload.take() {
+            match reload.await {
+                Ok(entities) => self.entities = entities,
+                Err(e) => error!("Failed to load SSH hosts: {}", e),
+            }
+            self.loaded = Some(Instant::now());
+        }
+        &self.entities
+    }
+}
+
+/// Hosts matching `query`, all of them for an empty query
+pub fn ssh_lookup(haystack: &Vec<FileEntity>, query: &str, matcher: &mut Matcher) -> Vec<LauncherEntity> {
+    if query.trim().is_empty() {
+        return haystack.iter()
+            .take(LISTED_HOSTS)
+            .map(|e| e.into_launcher_entity())
+            .collect();
+    }
+    search_entity(haystack, query.to_string(), matcher)
+}
+
+/// Opens a terminal running `ssh` through the terminal dispatcher
+pub fn ssh_entity(host: &SshHost) -> ProviderEntity {
+    let match_text = match &host.hostname {
+        Some(hostname) => format!("{} {}", host.name, hostname),
+        None => host.name.clone(),
+    };
+    ProviderEntity {
+        dispatcher: Dispatcher::Shell,
+        match_name: Utf32String::from(match_text),
+        match_rank: None,
+        path: PathBuf::new(),
+        ui_name: host.name.clone(),
+        provider: PROVIDER_NAME.to_string(),
+        subtitle: Some(host.target()),
+        icon: Some("network-server".to_string()),
+        action: Some(host.command()),
+    }
+}
+
+//
+// sources
+//
+
+/// Config hosts first, then known_hosts entries not already listed
+pub fn load_hosts(config: &SshConfig) -> Vec<SshHost> {
+    let Some(home) = std::env::var_os("HOME").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    let ssh_dir = home.join(".ssh");
+    let mut hosts = read_ssh_config(&ssh_dir.join("config"), &ssh_dir, &home);
+
+    if config.known_hosts {
+        match fs::read_to_string(ssh_dir.join("known_hosts")) {
+            Ok(contents) => hosts.extend(parse_known_hosts(&contents)),
+            Err(e) => warn!("Failed to read known_hosts: {}", e),
+        }
+    }
+
+    let mut seen = HashSet::new();
+    hosts.retain(|h| seen.insert(h.name.clone()));
+    hosts
+}
+
+/// `Host` entries of an ssh config file and the files it includes.
+/// Relative includes are looked up in `ssh_dir`, like ssh does for the user config.
+pub fn read_ssh_config(path: &Path, ssh_dir: &Path, home: &Path) -> Vec<SshHost> {
+    let mut hosts = Vec::new();
+    collect_config(path, ssh_dir, home, 0, &mut hosts);
+    hosts
+}
+
+fn collect_config(path: &Path, ssh_dir: &Path, home: &Path, depth: usize, hosts: &mut Vec<SshHost>) {
+    if depth > MAX_INCLUDE_DEPTH {
+        warn!("Not following ssh config includes deeper than {}: {}", MAX_INCLUDE_DEPTH, path.display());
+        return;
+    }
+    let Ok(contents) = fs::read_to_string(path) else { return };
+
+    // hosts of the `Host` line currently applying, settings below it fill them in
+    let mut block: Vec<usize> = Vec::new();
+    for line in contents.lines() {
+        let Some((keyword, args)) = split_directive(line) else { continue };
+        match keyword.to_ascii_lowercase().as_str() {
+            "host" => {
+                block.clear();
+                for name in args.split_whitespace().filter(|n| is_literal_host(n)) {
+                    block.push(hosts.len());
+                    hosts.push(SshHost::named(name, true));
+                }
+            },
+            "match" => block.clear(),
+            "include" => {
+                for pattern in args.split_whitespace() {
+                    let pattern = expand_home(Path::new(pattern), home);
+                    let pattern = if pattern.is_absolute() { pattern } else { ssh_dir.join(pattern) };
+                    for included in expand_glob(&pattern) {
+                        collect_config(&included, ssh_dir, home, depth + 1, hosts);
+                    }
+                }
+            },
+            setting => {
+                let value = args.split_whitespace().next().unwrap_or("").trim_matches('"');
+                for &i in &block {
+                    // the first value given for a setting is the one ssh uses
+                    let host = &mut hosts[i];
+                    match setting {
+                        "hostname" if host.hostname.is_none() => host.hostname = Some(value.to_string()),
+                        "user" if host.user.is_none() => host.user = Some(value.to_string()),
+                        "port" if host.port.is_none() => host.port = value.parse().ok(),
+                        _ => {},
+                    }
+                }
+            },
+        }
+    }
+}
+
+/// `Keyword args` or `Keyword=args`, none for comments and blank lines
+fn split_directive(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let end = line.find(|c: char| c.is_whitespace() || c == '=').unwrap_or(line.len());
+    let (keyword, rest) = line.split_at(end);
+    let args = rest.trim_start().strip_prefix('=').unwrap_or(rest).trim();
+    Some((keyword, args))
+}
+
+/// Patterns and negations can't be connected to
+fn is_literal_host(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['*', '?', '!'])
+}
+
+/// Paths matching `*` and `?` in the last component, sorted like ssh's glob
+fn expand_glob(pattern: &Path) -> Vec<PathBuf> {
+    let Some(name) = pattern.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        return Vec::new();
+    };
+    if !name.contains(['*', '?']) {
+        return vec![pattern.to_path_buf()];
+    }
+    let Some(dir) = pattern.parent() else { return Vec::new() };
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir).into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| wildcard_match(&name, &entry.file_name().to_string_lossy()))
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+    paths
+}
+
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // position of the last `*` and the text it was tried against, to backtrack to
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Host names of known_hosts lines. Hashed entries (`|1|…`) can't be read back, and
+/// `@cert-authority`/`@revoked` lines don't name hosts, so both are skipped.
+pub fn parse_known_hosts(contents: &str) -> Vec<SshHost> {
+    let mut hosts = Vec::new();
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(['#', '@', '|']) {
+            continue;
+        }
+        let Some(names) = line.split_whitespace().next() else { continue };
+        for name in names.split(',').filter(|n| is_literal_host(n)) {
+            hosts.push(known_host(name));
+        }
+    }
+    hosts
+}
+
+/// `host` or `[host]:port`
+fn known_host(name: &str) -> SshHost {
+    let bracketed = name.strip_prefix('[')
+        .and_then(|rest| rest.split_once("]:"))
+        .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)));
+    match bracketed {
+        Some((host, port)) => SshHost { port: Some(port), ..SshHost::named(host, false) },
+        None => SshHost::named(name, false),
+    }
+}
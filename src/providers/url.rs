@@ -0,0 +1,62 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::system::launch::shell_quote;
+
+const SCHEMES: &[&str] = &["http://", "https://", "file://", "ftp://"];
+/// Domains ending in these are taken for URLs when typed without a scheme.
+/// Short TLDs shared with file extensions, like `.sh` or `.rs`, are left out.
+const COMMON_TLDS: &[&str] = &[
+    "com", "org", "net", "io", "dev", "app", "edu", "gov", "info", "xyz", "ai",
+    "co", "uk", "de", "fr", "nl", "eu", "se", "no", "dk", "fi", "ch", "at", "jp", "ca", "au",
+];
+
+/// The URL to open for a typed query, with `https://` added when it has no scheme,
+/// or `http://` for local addresses
+pub fn as_url(query: &str) -> Option<String> {
+    let query = query.trim();
+    if query.is_empty() || query.contains(char::is_whitespace) {
+        return None;
+    }
+    if SCHEMES.iter().any(|s| query.starts_with(s)) {
+        return Some(query.to_string());
+    }
+
+    let host = query.split(['/', '?', '#']).next().unwrap_or("");
+    let domain = host.rsplit_once(':')
+        .filter(|(_, port)| !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()))
+        .map(|(domain, _)| domain)
+        .unwrap_or(host);
+    // local servers rarely have certificates
+    if is_loopback(domain) {
+        return Some(format!("http://{}", query));
+    }
+    if domain.starts_with("www.") || is_domain(domain) {
+        return Some(format!("https://{}", query));
+    }
+    None
+}
+
+/// `localhost`, its subdomains, `127.0.0.0/8` and `[::1]`
+fn is_loopback(domain: &str) -> bool {
+    let domain = domain.to_lowercase();
+    if domain == "localhost" || domain.ends_with(".localhost") {
+        return true;
+    }
+    match domain.strip_prefix('[').and_then(|d| d.strip_suffix(']')) {
+        Some(ipv6) => ipv6.parse::<Ipv6Addr>().is_ok_and(|ip| ip.is_loopback()),
+        None => domain.parse::<Ipv4Addr>().is_ok_and(|ip| ip.is_loopback()),
+    }
+}
+
+fn is_domain(domain: &str) -> bool {
+    let labels: Vec<&str> = domain.split('.').collect();
+    let Some(tld) = labels.last() else { return false };
+    labels.len() >= 2
+        && COMMON_TLDS.contains(&tld.to_lowercase().as_str())
+        && labels.iter().all(|l| !l.is_empty() && l.chars().all(|c| c.is_alphanumeric() || c == '-'))
+}
+
+/// Shell command opening `url` with the default handler
+pub fn open_url_command(url: &str) -> String {
+    format!("xdg-open {}", shell_quote(url))
+}
@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use nucleo::Utf32String;
+
+use crate::{
+    config::config::{SearchEngineConfig, WebSearchConfig},
+    providers::url::open_url_command,
+    search::entity_model::{Dispatcher, Export, FileEntity, LauncherEntity, ProviderEntity},
+};
+
+const QUERY_PLACEHOLDER: &str = "{query}";
+
+/// The engine whose keyword starts `query`, and the search terms after it
+pub fn match_engine<'a>(config: &'a WebSearchConfig, query: &'a str) -> Option<(&'a SearchEngineConfig, &'a str)> {
+    config.engines.iter().find_map(|engine| {
+        let terms = query.strip_prefix(engine.keyword.as_str())?.strip_prefix(' ')?;
+        Some((engine, terms))
+    })
+}
+
+/// A row opening the search, none until search terms are typed
+pub fn web_search_lookup(engine: &SearchEngineConfig, terms: &str) -> Vec<LauncherEntity> {
+    let terms = terms.trim();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+    vec![FileEntity::Provider(web_search_entity(engine, terms)).into_launcher_entity()]
+}
+
+/// Search with the fallback engine, offered below the local results
+pub fn fallback_lookup(config: &WebSearchConfig, terms: &str) -> Vec<LauncherEntity> {
+    config.fallback.as_ref()
+        .and_then(|keyword| config.engines.iter().find(|e| &e.keyword == keyword))
+        .map(|engine| web_search_lookup(engine, terms))
+        .unwrap_or_default()
+}
+
+pub fn web_search_entity(engine: &SearchEngineConfig, terms: &str) -> ProviderEntity {
+    let url = search_url(&engine.url, terms);
+    let title = format!("Search {} for \u{201c}{}\u{201d}", engine.name, terms);
+    ProviderEntity {
+        dispatcher: Dispatcher::Hyprctl,
+        match_name: Utf32String::from(terms),
+        match_rank: None,
+        path: PathBuf::new(),
+        ui_name: title,
+        provider: engine.name.clone(),
+        subtitle: Some(url.clone()),
+        icon: Some("system-search".to_string()),
+        action: Some(open_url_command(&url)),
+    }
+}
+
+/// Fill the template's `{query}` with the encoded terms, or append them when it has none
+pub fn search_url(template: &str, terms: &str) -> String {
+    let encoded = percent_encode(terms);
+    if template.contains(QUERY_PLACEHOLDER) {
+        template.replace(QUERY_PLACEHOLDER, &encoded)
+    } else {
+        format!("{}{}", template, encoded)
+    }
+}
+
+/// Encode everything but RFC 3986 unreserved characters, byte by byte of the UTF-8
+pub fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
@@ -0,0 +1,140 @@
+use std::cmp::Reverse;
+
+use nucleo::{Matcher, Utf32String};
+
+use crate::search::{
+    entity_model::{FileEntity, LauncherEntity},
+    script_meta::ScriptArg,
+};
+
+/// Prompt shown when arguments are entered without a declared parameter (Tab)
+const FREE_FORM_LABEL: &str = "arguments";
+
+/// Second input stage collecting arguments for the selected entity, one parameter at a time.
+#[derive(Clone)]
+pub struct ArgPrompt {
+    entity: LauncherEntity,
+    params: Vec<ScriptArg>,
+    values: Vec<String>,
+}
+pub enum PromptStep {
+    /// Waiting for the next parameter
+    Next,
+    /// All arguments collected, ready to run
+    Done(Box<LauncherEntity>),
+}
+impl ArgPrompt {
+    /// Prompt for the arguments a script declares, None if it declares none
+    pub fn declared(entity: &LauncherEntity) -> Option<Self> {
+        let FileEntity::Executable(e) = &entity.file_entity else {
+            return None;
+        };
+        let params = e.script.as_ref()
+            .map(|s| s.args.clone())
+            .filter(|args| !args.is_empty())?;
+
+        Some(ArgPrompt { entity: entity.clone(), params, values: Vec::new() })
+    }
+    /// Prompt for any arguments, split into words like the shell does, see `split_words`
+    pub fn free_form(entity: &LauncherEntity) -> Self {
+        ArgPrompt { entity: entity.clone(), params: Vec::new(), values: Vec::new() }
+    }
+    fn is_free_form(&self) -> bool {
+        self.params.is_empty()
+    }
+    fn current(&self) -> Option<&ScriptArg> {
+        self.params.get(self.values.len())
+    }
+    /// Prompt label, e.g. `remote (2/2)`
+    pub fn label(&self) -> String {
+        match self.current() {
+            Some(param) if self.params.len() > 1 => {
+                format!("{} ({}/{})", param.name, self.values.len() + 1, self.params.len())
+            },
+            Some(param) => param.name.clone(),
+            None => FREE_FORM_LABEL.to_string(),
+        }
+    }
+    /// Choices of the current parameter matching `query`, best match first. All when `query` is empty.
+    pub fn complete(&self, query: &str, matcher: &mut Matcher) -> Vec<String> {
+        let Some(param) = self.current() else {
+            return Vec::new();
+        };
+        if query.is_empty() {
+            return param.choices.clone();
+        }
+
+        let needle = Utf32String::from(query);
+        let mut scored: Vec<(u16, &String)> = param.choices.iter()
+            .filter_map(|choice| {
+                matcher.fuzzy_match(Utf32String::from(choice.as_str()).slice(..), needle.slice(..))
+                    .map(|score| (score, choice))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| Reverse(*score));
+        scored.into_iter().map(|(_, choice)| choice.clone()).collect()
+    }
+    pub fn has_choices(&self) -> bool {
+        self.current().is_some_and(|p| !p.choices.is_empty())
+    }
+    /// Accept a value for the current parameter
+    pub fn submit(&mut self, value: &str) -> PromptStep {
+        if self.is_free_form() {
+            self.values.extend(split_words(value));
+        }
+        else {
+            self.values.push(value.to_string());
+            if self.values.len() < self.params.len() {
+                return PromptStep::Next;
+            }
+        }
+
+        let mut entity = self.entity.clone();
+        entity.launch.args = std::mem::take(&mut self.values);
+        PromptStep::Done(Box::new(entity))
+    }
+}
+
+/// Split free-form input on whitespace. Single or double quotes keep whitespace in a word,
+/// and a backslash outside single quotes takes the next character literally,
+/// e.g. `"my file.txt" it\'s` gives `my file.txt` and `it's`. An unclosed quote runs to the end.
+pub fn split_words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    // a word was started, so that `""` gives an empty argument
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => word.push(c),
+            (_, '\\') => {
+                if let Some(next) = chars.next() {
+                    word.push(next);
+                }
+                in_word = true;
+            },
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            },
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            },
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            },
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
@@ -6,6 +6,8 @@ use nucleo::Utf32String;
 
 use crate::search::entity_model::{Dispatcher, ExecutableEntity, ExecutableSource};
 use crate::search::error::EntityError;
+use crate::search::script_meta::{read_script_meta, ScriptMode};
+use crate::system::launch::shell_quote;
 
 pub fn scan_path_executables() -> Vec<ExecutableEntity> {
     let mut executables = Vec::new();
@@ -28,6 +30,9 @@ pub fn scan_path_executables() -> Vec<ExecutableEntity> {
             path,
 
             source: ExecutableSource::PathBinary,
+            wm_class: None,
+            working_dir: None,
+            script: None,
         });
     }
     executables
@@ -80,7 +85,11 @@ pub fn scan_desktop_executables(extra: Vec<PathBuf>) -> Vec<ExecutableEntity> {
         // TODO: icons?
         // let icon = entry.icon();
 
-        let path = entry.path;  // TODO: rename field: this is the working directory to run program in
+        let wm_class = entry.startup_wm_class().map(|c| c.to_string());
+        let working_dir = entry.desktop_entry("Path")
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from);
+        let path = entry.path;  // the desktop file, see ExecutableEntity::app_id()
 
         executables.push(ExecutableEntity {
             dispatcher,
@@ -91,6 +100,9 @@ pub fn scan_desktop_executables(extra: Vec<PathBuf>) -> Vec<ExecutableEntity> {
 
             source: ExecutableSource::DesktopFile,
             exec,
+            wm_class,
+            working_dir,
+            script: None,
         });
     }
     executables
@@ -100,37 +112,60 @@ pub fn scan_script_executables(config_paths: &Vec<PathBuf>) -> Vec<ExecutableEnt
     let mut all_scripts = Vec::<ExecutableEntity>::new();
     for dir_path in config_paths {
         if let Ok(scripts) = find_scripts_in_path(&dir_path) {
-            for (path, ui_name) in scripts {
-                all_scripts.push(
-                    ExecutableEntity {
-                        dispatcher: Dispatcher::Shell,
-                        match_name: Utf32String::from(ui_name.clone()),
-                        match_rank: None,
-                        path,
-                        ui_name,
-
-                        source: ExecutableSource::ShellScript,
-                        exec: "".to_string(),
-                    }
-                );
+            for (path, file_name) in scripts {
+                all_scripts.push(script_entity(path, file_name));
             }
         }
     }
     all_scripts
 }
 
+/// Script named by its `shunpo-name` header or file name, matched on name and keywords
+pub fn script_entity(path: PathBuf, file_name: String) -> ExecutableEntity {
+    let script = read_script_meta(&path);
+    let meta = script.clone().unwrap_or_default();
+
+    let ui_name = meta.name.unwrap_or(file_name);
+    let match_name = Utf32String::from(
+        std::iter::once(ui_name.as_str())
+            .chain(meta.keywords.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    let dispatcher = match meta.mode {
+        ScriptMode::Terminal => Dispatcher::Shell,
+        ScriptMode::Background => Dispatcher::Background,
+    };
+
+    ExecutableEntity {
+        dispatcher,
+        match_name,
+        match_rank: None,
+        exec: script_exec(&path),
+        path,
+        ui_name,
+
+        source: ExecutableSource::ShellScript,
+        wm_class: None,
+        working_dir: None,
+        script,
+    }
+}
+
+fn script_exec(path: &PathBuf) -> String {
+    let quoted = shell_quote(&path.to_string_lossy());
+    if is_executable(path) { quoted } else { format!("sh {}", quoted) }
+}
+
+/// Executable files, and `.sh` files which are run with `sh`
 fn find_scripts_in_path(dir: &PathBuf) -> Result<Vec<(PathBuf, String)>, EntityError> {
     let paths = std::fs::read_dir(dir)
         .map_err(|_| EntityError::WIP)?
         .filter_map(|res| res.ok())
-        .map(|dir_entry| (dir_entry.path(), dir_entry.file_name()))
-        .filter_map(|(path, file)| {
-            if path.extension().map_or(false, |ext| ext == "sh") {
-                Some((path, file.to_string_lossy().into_owned()))
-            }
-            else {
-                None
-            }
+        .map(|dir_entry| (dir_entry.path(), dir_entry.file_name().to_string_lossy().into_owned()))
+        .filter(|(path, file)| !file.starts_with('.') && path.is_file())
+        .filter(|(path, _)| {
+            path.extension().map_or(false, |ext| ext == "sh") || is_executable(path)
         })
         .collect::<Vec<(_, _)>>();
     Ok(paths)
@@ -144,11 +179,12 @@ fn find_executables_in_path(path_env: &str) -> impl Iterator<Item = PathBuf> + '
         .filter_map(|entry| entry.ok())
         .map(|entry| entry.path())
         .filter(|path| path.is_file())
-        .filter(|path| {
-            fs::metadata(path)
-                .map(|m| m.permissions().mode() & 0o111 != 0)
-                .unwrap_or(false)
-        })
+        .filter(|path| is_executable(path))
+}
+fn is_executable(path: &PathBuf) -> bool {
+    fs::metadata(path)
+        .map(|m| m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
 }
 fn name_from_path(path: &PathBuf) -> String {
     path.file_name()
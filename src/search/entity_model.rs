@@ -1,11 +1,15 @@
 use std::{collections::HashMap, fmt::Display, path::PathBuf};
 use nucleo::Utf32String;
 
+use crate::{hyprland::commands::HyprCommand, search::{launch_options::LaunchOptions, script_meta::ScriptMeta}};
+
 #[derive(Clone)]
 pub enum FileEntity {
     Executable(ExecutableEntity),
     Ripgrep(RipgrepEntity),
     Virtual(VirtualEntity),
+    HyprCommand(HyprCommandEntity),
+    Provider(ProviderEntity),
     // Image,
     // Audio,
 }
@@ -15,6 +19,9 @@ pub enum Dispatcher {
     Shell,
     Virtual,
     Custom,
+    HyprCommand,
+    /// Without a window, output shown in the launcher
+    Background,
 }
 impl Display for Dispatcher {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -23,6 +30,8 @@ impl Display for Dispatcher {
             Dispatcher::Shell => write!(f, "Shell"),
             Dispatcher::Virtual => write!(f, "Virtual"),
             Dispatcher::Custom => write!(f, "Custom"),
+            Dispatcher::HyprCommand => write!(f, "HyprCommand"),
+            Dispatcher::Background => write!(f, "Background"),
         }
     }
 }
@@ -66,17 +75,19 @@ pub struct LauncherEntity {
     pub command: String,
     pub dispatcher: Dispatcher,
     pub file_entity: FileEntity,
+    pub launch: LaunchOptions,
 }
 impl LauncherEntity {
     pub fn from_executable(entity: &ExecutableEntity) -> Self {
         let command = match entity.source {
-            ExecutableSource::DesktopFile => { entity.exec.clone() },
+            ExecutableSource::DesktopFile | ExecutableSource::ShellScript => { entity.exec.clone() },
             _  => { entity.path.to_string_lossy().to_string() },
         };
         LauncherEntity {
             command,
             dispatcher: entity.dispatcher.clone(),
             file_entity: FileEntity::Executable(entity.to_owned()),
+            launch: LaunchOptions::default(),
         }
     }
     pub fn from_ripgrep(entity: &RipgrepEntity) -> Self {
@@ -84,6 +95,7 @@ impl LauncherEntity {
             command: entity.path.clone().to_string_lossy().to_string(),
             dispatcher: entity.dispatcher.clone(),
             file_entity: FileEntity::Ripgrep(entity.to_owned()),
+            launch: LaunchOptions::default(),
         }
     }
     pub fn from_virtual(entity: &VirtualEntity) -> Self {
@@ -91,6 +103,23 @@ impl LauncherEntity {
             command: "".into(),
             dispatcher: entity.dispatcher.clone(),
             file_entity: FileEntity::Virtual(entity.to_owned()),
+            launch: LaunchOptions::default(),
+        }
+    }
+    pub fn from_hypr_command(entity: &HyprCommandEntity) -> Self {
+        LauncherEntity {
+            command: entity.command.to_string(),
+            dispatcher: entity.dispatcher.clone(),
+            file_entity: FileEntity::HyprCommand(entity.to_owned()),
+            launch: LaunchOptions::default(),
+        }
+    }
+    pub fn from_provider(entity: &ProviderEntity) -> Self {
+        LauncherEntity {
+            command: entity.action.clone().unwrap_or_default(),
+            dispatcher: entity.dispatcher.clone(),
+            file_entity: FileEntity::Provider(entity.to_owned()),
+            launch: LaunchOptions::default(),
         }
     }
 }
@@ -106,15 +135,62 @@ pub struct VirtualEntity {
     pub match_rank: Option<u16>,
     pub path: PathBuf,
     pub ui_name: String,
+
+    pub action: VirtualAction,
+}
+/// What dispatching a virtual entity does
+#[derive(Clone, Debug, PartialEq)]
+pub enum VirtualAction {
+    None,
+    RandomWallpaper,
+    /// Put the text on the clipboard
+    CopyToClipboard(String),
+    /// Put a clipboard history entry back on the clipboard
+    RestoreClip(String),
+    /// Type the text into the window focused after the launcher hides
+    TypeText(String),
 }
 impl VirtualEntity {
+    /// Informational result, e.g. help text. Dispatching it does nothing.
     pub fn no_dispatch(text: String) -> Self {
+        Self::with_action(text, VirtualAction::None)
+    }
+    pub fn with_action(text: String, action: VirtualAction) -> Self {
         VirtualEntity {
             dispatcher: Dispatcher::Virtual,
             match_name: "".into(),
             match_rank: None,
             path: PathBuf::new(),
             ui_name: text,
+            action,
+        }
+    }
+}
+
+//
+// Hyprland commands
+//
+
+#[derive(Clone)]
+pub struct HyprCommandEntity {
+    pub dispatcher: Dispatcher,
+    pub match_name: Utf32String,
+    #[allow(dead_code)] // TODO: pending impl of weight-by-use results
+    pub match_rank: Option<u16>,
+    pub path: PathBuf,
+    pub ui_name: String,
+
+    pub command: HyprCommand,
+}
+impl HyprCommandEntity {
+    pub fn new(command: HyprCommand) -> Self {
+        HyprCommandEntity {
+            dispatcher: Dispatcher::HyprCommand,
+            match_name: "".into(),
+            match_rank: None,
+            path: PathBuf::new(),
+            ui_name: command.preview(),
+            command,
         }
     }
 }
@@ -134,6 +210,22 @@ pub struct ExecutableEntity {
 
     pub source: ExecutableSource,
     pub exec: String,
+    /// Desktop entry `StartupWMClass`, the initial class of windows it opens
+    pub wm_class: Option<String>,
+    /// Desktop entry `Path`, the directory to run the program in
+    pub working_dir: Option<PathBuf>,
+    /// Header of scripts from `script_paths`
+    pub script: Option<ScriptMeta>,
+}
+impl ExecutableEntity {
+    /// Id used to find per-app config: desktop file id, or executable file name.
+    pub fn app_id(&self) -> String {
+        let id = match self.source {
+            ExecutableSource::DesktopFile => self.path.file_stem(),
+            _ => self.path.file_name(),
+        };
+        id.map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+    }
 }
 #[derive(Clone)]
 pub enum ExecutableSource {
@@ -158,6 +250,28 @@ pub struct RipgrepEntity {
     pub line: i32,
 }
 
+//
+// External providers
+//
+
+#[derive(Clone)]
+pub struct ProviderEntity {
+    pub dispatcher: Dispatcher,
+    pub match_name: Utf32String,
+    #[allow(dead_code)] // TODO: pending impl of weight-by-use results
+    pub match_rank: Option<u16>,
+    pub path: PathBuf,
+    pub ui_name: String,
+
+    /// Name of the provider which returned this result
+    pub provider: String,
+    pub subtitle: Option<String>,
+    /// Icon name from the icon theme
+    pub icon: Option<String>,
+    /// Shell command run when the result is chosen, informational results have none
+    pub action: Option<String>,
+}
+
 //
 // FileEntity trait impls
 //
@@ -169,6 +283,8 @@ impl EntityFields for FileEntity {
             Self::Executable(e) => &e.dispatcher,
             Self::Ripgrep(r) => &r.dispatcher,
             Self::Virtual(v) => &v.dispatcher,
+            Self::HyprCommand(h) => &h.dispatcher,
+            Self::Provider(p) => &p.dispatcher,
         }
     }
     fn path(&self) -> &PathBuf {
@@ -176,6 +292,8 @@ impl EntityFields for FileEntity {
             Self::Executable(e) => &e.path,
             Self::Ripgrep(r) => &r.path,
             Self::Virtual(v) => &v.path,
+            Self::HyprCommand(h) => &h.path,
+            Self::Provider(p) => &p.path,
         }
     }
 }
@@ -185,6 +303,8 @@ impl Matching for FileEntity {
             Self::Executable(e) => &e.match_name,
             Self::Ripgrep(r) => &r.match_name,
             Self::Virtual(v) => &v.match_name,
+            Self::HyprCommand(h) => &h.match_name,
+            Self::Provider(p) => &p.match_name,
         }
     }
     fn match_rank(&self) -> Option<u16> {
@@ -192,6 +312,8 @@ impl Matching for FileEntity {
             Self::Executable(e) => e.match_rank,
             Self::Ripgrep(r) => r.match_rank,
             Self::Virtual(v) => v.match_rank,
+            Self::HyprCommand(h) => h.match_rank,
+            Self::Provider(p) => p.match_rank,
         }
     }
     fn set_match_rank(&mut self, rank: u16) {
@@ -199,6 +321,8 @@ impl Matching for FileEntity {
             Self::Executable(e) => e.match_rank = Some(rank),
             Self::Ripgrep(r) => r.match_rank = Some(rank),
             Self::Virtual(v) => v.match_rank = Some(rank),
+            Self::HyprCommand(h) => h.match_rank = Some(rank),
+            Self::Provider(p) => p.match_rank = Some(rank),
         }
     }
 }
@@ -208,6 +332,8 @@ impl Export for FileEntity {
             Self::Executable(e) => &e.ui_name,
             Self::Ripgrep(r) => &r.ui_name,
             Self::Virtual(v) => &v.ui_name,
+            Self::HyprCommand(h) => &h.ui_name,
+            Self::Provider(p) => &p.ui_name,
         }
     }
     fn into_entity(self) -> FileEntity {
@@ -215,6 +341,8 @@ impl Export for FileEntity {
             Self::Executable(e) => FileEntity::Executable(e),
             Self::Ripgrep(r) => FileEntity::Ripgrep(r),
             Self::Virtual(v) => FileEntity::Virtual(v),
+            Self::HyprCommand(h) => FileEntity::HyprCommand(h),
+            Self::Provider(p) => FileEntity::Provider(p),
         }
     }
     fn into_launcher_entity(&self) -> LauncherEntity {
@@ -222,6 +350,8 @@ impl Export for FileEntity {
             Self::Executable(e) => { LauncherEntity::from_executable(e) },
             Self::Ripgrep(r) => { LauncherEntity::from_ripgrep(r) },
             Self::Virtual(v) => { LauncherEntity::from_virtual(v) },
+            Self::HyprCommand(h) => { LauncherEntity::from_hypr_command(h) },
+            Self::Provider(p) => { LauncherEntity::from_provider(p) },
         }
     }
 }
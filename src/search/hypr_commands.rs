@@ -0,0 +1,44 @@
+use crate::{
+    hyprland::{commands::{HyprCommand, HYPR_COMMANDS}, error::HyprError},
+    search::entity_model::{HyprCommandEntity, LauncherEntity, VirtualEntity},
+};
+
+/// Results for `:` queries.
+/// A valid command previews what it will do, invalid arguments show usage,
+/// and a partial command name lists the matching commands.
+pub fn hypr_command_lookup(query: &str) -> Vec<LauncherEntity> {
+    match HyprCommand::parse(query) {
+        Some(Ok(cmd)) => {
+            vec![LauncherEntity::from_hypr_command(&HyprCommandEntity::new(cmd))]
+        },
+        Some(Err(e)) => {
+            let reason = match e {
+                HyprError::CommandInvalidArgs(reason) => reason,
+                other => other.to_string(),
+            };
+            let name = command_name(query);
+            HYPR_COMMANDS.iter()
+                .filter(|(cmd, _, _)| *cmd == name)
+                .map(|(_, usage, _)| hint(format!("{}: {}", usage, reason)))
+                .collect()
+        },
+        None => {
+            let name = command_name(query);
+            HYPR_COMMANDS.iter()
+                .filter(|(cmd, _, _)| cmd.starts_with(name))
+                .map(|(_, usage, description)| hint(format!("{} - {}", usage, description)))
+                .collect()
+        },
+    }
+}
+
+fn command_name(query: &str) -> &str {
+    query.trim_start_matches(':')
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+}
+
+fn hint(text: String) -> LauncherEntity {
+    LauncherEntity::from_virtual(&VirtualEntity::no_dispatch(text))
+}
@@ -0,0 +1,152 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    config::config::AppConfig,
+    hyprland::structs::MonitorName,
+    search::entity_model::{ExecutableEntity, ExecutableSource},
+    system::launch::shell_quote,
+};
+
+/// Per-launch settings carried by a LauncherEntity, on top of the per-app config.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LaunchOptions {
+    /// Hyprland exec rules, e.g. `workspace 3 silent`
+    pub exec_rules: Vec<String>,
+    /// Start a new instance even if the app already has a window (Shift+Return)
+    pub force_new: bool,
+    /// Keep the terminal open after the command exits
+    pub hold: bool,
+    /// Working directory, `~` is expanded
+    pub cwd: Option<PathBuf>,
+    /// Arguments entered in the argument prompt
+    pub args: Vec<String>,
+    /// `@` tokens which are no option, workspace or connected monitor, e.g. `@floatt`
+    pub unknown: Vec<String>,
+}
+
+/// Rules which are used as they are, e.g. `@float`
+const RULE_KEYWORDS: &[&str] = &["float", "tile", "fullscreen", "pin"];
+
+/// Split trailing `@` tokens from a query into launch options.
+///
+/// - `@hold` -> keep the terminal open
+/// - `@cwd:~/src` -> run in `~/src`
+/// - `@3`, `@special:magic`, `@name:code` -> `workspace 3 silent`
+/// - `@float`, `@tile`, `@fullscreen`, `@pin` -> the rule itself
+/// - `@DP-2`, one of `monitors` -> `monitor DP-2`
+/// - anything else is kept in `unknown`
+///
+/// e.g. `firefox @3 @float` -> (`firefox`, [`workspace 3 silent`, `float`])
+pub fn split_launch_options(query: &str, monitors: &[MonitorName]) -> (String, LaunchOptions) {
+    let mut words: Vec<&str> = query.split(' ').collect();
+    let mut options = LaunchOptions::default();
+
+    while let Some(token) = words.last().and_then(|w| w.strip_prefix('@')) {
+        if token == "hold" {
+            options.hold = true;
+        }
+        else if let Some(dir) = token.strip_prefix("cwd:") {
+            // the last `@cwd:` wins, tokens are read from the end
+            if options.cwd.is_none() && !dir.is_empty() {
+                options.cwd = Some(PathBuf::from(dir));
+            }
+        }
+        // a lone `@` is still being typed
+        else if !token.is_empty() {
+            match exec_rule(token, monitors) {
+                Some(rule) => options.exec_rules.push(rule),
+                None => options.unknown.push(format!("@{}", token)),
+            }
+        }
+        words.pop();
+    }
+    options.exec_rules.reverse();
+    options.unknown.reverse();
+
+    (words.join(" ").trim_end().to_string(), options)
+}
+
+fn exec_rule(token: &str, monitors: &[MonitorName]) -> Option<String> {
+    let workspace = token.parse::<u32>().is_ok()
+        || token == "special"
+        || token.strip_prefix("special:").is_some_and(|name| !name.is_empty())
+        || token.strip_prefix("name:").is_some_and(|name| !name.is_empty());
+
+    if workspace {
+        Some(format!("workspace {} silent", token))
+    }
+    else if RULE_KEYWORDS.contains(&token) {
+        Some(token.to_string())
+    }
+    else if monitors.iter().any(|m| m.to_string() == token) {
+        Some(format!("monitor {}", token))
+    }
+    else {
+        None
+    }
+}
+
+/// Names of the `@` tokens a query may end with, listed when one isn't understood
+pub fn launch_option_help(monitors: &[MonitorName]) -> String {
+    let monitors: Vec<String> = monitors.iter().map(|m| format!("@{}", m)).collect();
+    let keywords: Vec<String> = RULE_KEYWORDS.iter().map(|k| format!("@{}", k)).collect();
+    format!(
+        "@[workspace], @special:[name], {}, {}@hold, @cwd:[dir]",
+        keywords.join(", "),
+        monitors.iter().map(|m| format!("{}, ", m)).collect::<String>(),
+    )
+}
+
+/// Working directory for a launch: chosen for this launch, the app's config, the desktop
+/// entry's `Path`, then the script's directory. Terminals fall back to `home`, other apps
+/// start in Hyprland's working directory.
+pub fn resolve_working_dir(
+    entity: &ExecutableEntity,
+    launch: &LaunchOptions,
+    app: Option<&AppConfig>,
+    in_terminal: bool,
+    home: Option<&Path>,
+) -> Option<PathBuf> {
+    let script_dir = match entity.source {
+        ExecutableSource::ShellScript => entity.path.parent().map(Path::to_path_buf),
+        _ => None,
+    };
+    let dir = launch.cwd.clone()
+        .or_else(|| app.and_then(|a| a.cwd.clone()))
+        .or_else(|| entity.working_dir.clone())
+        .or(script_dir);
+
+    match (dir, home) {
+        (Some(dir), Some(home)) => Some(expand_home(&dir, home)),
+        (Some(dir), None) => Some(dir),
+        (None, Some(home)) if in_terminal => Some(home.to_path_buf()),
+        (None, _) => None,
+    }
+}
+
+/// Keep the terminal open: chosen for this launch, the app's config, then `hold_scripts` for scripts.
+pub fn resolve_hold(
+    entity: &ExecutableEntity,
+    launch: &LaunchOptions,
+    app: Option<&AppConfig>,
+    hold_scripts: bool,
+) -> bool {
+    let default = matches!(entity.source, ExecutableSource::ShellScript) && hold_scripts;
+    launch.hold || app.and_then(|a| a.hold).unwrap_or(default)
+}
+
+/// Replace a leading `~` with `home`
+pub fn expand_home(path: &Path, home: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => home.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Append arguments to a shell command, each quoted as a single word
+pub fn append_args(cmd: &str, args: &[String]) -> String {
+    std::iter::once(cmd.to_string())
+        .chain(args.iter().map(|a| shell_quote(a)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -1,4 +1,6 @@
-use log::info;
+use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+
+use log::{error, info};
 use tokio::sync::mpsc;
 use nucleo;
 
@@ -6,9 +8,11 @@ use crate::{
     config::config::ShunpoConfig, coordinator::types::{
         CoordinatorMessage,
         SearchMessageData
-    }, rg::rg::rg_lookup, search::{
-        entity_model::FileEntity,
+    }, providers::{bookmarks::{bookmark_lookup, url_lookup, BookmarkStore, BOOKMARK_PREFIX}, calculator::{calc_lookup, CALC_PREFIX}, clipboard::{clip_lookup, SharedHistory, CLIP_PREFIX}, emoji::{emoji_entities, emoji_lookup, EMOJI_PREFIX}, external::{match_provider, provider_lookup}, recent_files::{recent_lookup, RecentFileStore, RECENT_PREFIX}, ssh::{ssh_lookup, SshHostStore, SSH_PREFIX}, web_search::{fallback_lookup, match_engine, web_search_lookup}}, hyprland::{hyprctl::get_monitors, structs::MonitorName}, rg::rg::rg_lookup, search::{
+        entity_model::{Export, FileEntity, VirtualEntity},
         entity_repository::{EntityRepository, RepositoryConfig},
+        hypr_commands::hypr_command_lookup,
+        launch_options::{launch_option_help, split_launch_options},
         matcher_helpers::search_entity
     }
 };
@@ -16,16 +20,18 @@ use crate::{
 pub fn setup_search_listener(
     search_rx: mpsc::UnboundedReceiver<String>,
     search_coord_tx: mpsc::UnboundedSender<CoordinatorMessage>,
-    config: ShunpoConfig
+    config: ShunpoConfig,
+    clipboard: SharedHistory,
 ){
     tokio::spawn(async {
-        search_listener(search_rx, search_coord_tx, config).await;
+        search_listener(search_rx, search_coord_tx, config, clipboard).await;
     });
 }
 async fn search_listener(
     mut search_rx: mpsc::UnboundedReceiver<String>,
     search_coord_tx: mpsc::UnboundedSender<CoordinatorMessage>,
-    config: ShunpoConfig
+    config: ShunpoConfig,
+    clipboard: SharedHistory,
 ){
     let mut matcher = nucleo::Matcher::new(nucleo::Config::DEFAULT);
     let mut haystack: &Vec<FileEntity>;
@@ -33,41 +39,156 @@ async fn search_listener(
     let repo_config = RepositoryConfig::from_shunpo_config(&config);
     let mut entity_repo = EntityRepository::new(repo_config);
     entity_repo.populate();
+    let emoji = emoji_entities(config.emoji.action);
+    let mut bookmarks = BookmarkStore::default();
+    let mut ssh_hosts = SshHostStore::default();
+    let mut recent_files = RecentFileStore::default();
+
+    // a query which arrived while a provider was running
+    let mut queued: Option<String> = None;
 
-    loop {tokio::select! {
-        Some(msg) = search_rx.recv() => {
-            let results;
-            // skip empty search queries
-            if msg.is_empty() || msg.starts_with(":") {
-                let _ = search_coord_tx.send(CoordinatorMessage::SearchMessage(SearchMessageData {
-                    results: Vec::new(),
-                }));
-                continue;
+    loop {
+        let msg = match queued.take() {
+            Some(msg) => msg,
+            None => match search_rx.recv().await {
+                Some(msg) => msg,
+                None => {
+                    info!("Search channel closed, exiting listener.");
+                    break;
+                },
+            },
+        };
+        // only the latest of the queries typed meanwhile is searched
+        let msg = latest_query(msg, &mut search_rx);
+
+        let mut results;
+        // skip empty search queries
+        if msg.is_empty() {
+            let _ = search_coord_tx.send(CoordinatorMessage::SearchMessage(SearchMessageData {
+                results: Vec::new(),
+                query: Some(msg),
+            }));
+            continue;
+        }
+        else if let Some((provider, query)) = match_provider(&config.providers, &msg) {
+            // providers run off the runtime, and are stopped once a newer query arrives
+            let cancel = Arc::new(AtomicBool::new(false));
+            let lookup = tokio::task::spawn_blocking({
+                let (provider, query, cancel) = (provider.clone(), query.to_string(), cancel.clone());
+                move || provider_lookup(&provider, &query, &cancel)
+            });
+            results = tokio::select! {
+                lookup = lookup => match lookup {
+                    Ok(Ok(results)) => results,
+                    Ok(Err(e)) => {
+                        error!("Provider {}: {}", provider.name, e);
+                        vec![]
+                    },
+                    Err(e) => {
+                        error!("Provider {} task failed: {}", provider.name, e);
+                        vec![]
+                    },
+                },
+                newer = search_rx.recv() => {
+                    cancel.store(true, Ordering::Relaxed);
+                    queued = newer;
+                    continue;
+                },
+            };
+        }
+        else if msg.starts_with(CALC_PREFIX) {
+            results = calc_lookup(&msg);
+        }
+        else if let Some(query) = msg.strip_prefix(CLIP_PREFIX) {
+            results = match clipboard.lock() {
+                Ok(history) => clip_lookup(&history, query, &mut matcher),
+                Err(_) => vec![],
+            };
+            if results.is_empty() && !config.clipboard.enabled {
+                let hint = "Clipboard history is off, set `enabled = true` under [clipboard] to keep one";
+                results = vec![FileEntity::Virtual(VirtualEntity::no_dispatch(hint.to_string())).into_launcher_entity()];
             }
-            else if msg.starts_with("rg ") {
-                results = rg_lookup(&msg[2..], &config).unwrap_or_else(|_| vec![]);
+        }
+        else if let Some(query) = msg.strip_prefix(EMOJI_PREFIX) {
+            results = emoji_lookup(&emoji, query, &mut matcher);
+        }
+        else if let Some(query) = msg.strip_prefix(BOOKMARK_PREFIX) {
+            results = bookmark_lookup(bookmarks.entities(&config.bookmarks).await, query, &mut matcher);
+        }
+        else if let Some(query) = msg.strip_prefix(SSH_PREFIX) {
+            results = ssh_lookup(ssh_hosts.entities(&config.ssh).await, query, &mut matcher);
+        }
+        else if let Some(query) = msg.strip_prefix(RECENT_PREFIX) {
+            results = recent_lookup(recent_files.entities().await, query, &mut matcher);
+        }
+        else if msg.starts_with(":") {
+            results = hypr_command_lookup(&msg);
+        }
+        else if msg.starts_with("rg ") {
+            results = rg_lookup(&msg[2..], &config).unwrap_or_else(|_| vec![]);
+        }
+        else if let Some((engine, terms)) = match_engine(&config.web_search, &msg) {
+            results = web_search_lookup(engine, terms);
+        }
+        else {
+            // `@` suffixes choose where and how the app opens
+            let monitors = connected_monitors(&msg).await;
+            let (query, launch) = split_launch_options(&msg, &monitors);
+
+            if !launch.unknown.is_empty() {
+                let help = format!("Unknown {}, use {}", launch.unknown.join(" "), launch_option_help(&monitors));
+                results = vec![FileEntity::Virtual(VirtualEntity::no_dispatch(help)).into_launcher_entity()];
             }
-            else if msg.starts_with("b ") {
+            else if query.starts_with("b ") {
                 haystack = entity_repo.get_generic_executables();
-                results = search_entity(&haystack, msg[2..].to_string(), &mut matcher);
+                results = search_entity(&haystack, query[2..].to_string(), &mut matcher);
             }
-            else if msg.starts_with("! ") {
+            else if query.starts_with("! ") {
                 haystack = entity_repo.get_generic_scripts();
-                results = search_entity(&haystack, msg[2..].to_string(), &mut matcher);
+                results = search_entity(&haystack, query[2..].to_string(), &mut matcher);
             }
             else {
                 haystack = entity_repo.get_generic_exec_desktop();
-                results = search_entity(&haystack, msg, &mut matcher);
+                // typed URLs and unit conversions, e.g. `12 km in mi`, are shown above matching apps
+                results = url_lookup(&msg);
+                results.extend(calc_lookup(&msg));
+                results.extend(search_entity(&haystack, query.clone(), &mut matcher));
+                // fuzzy matching finds some app for most words, so the web search comes last
+                results.extend(fallback_lookup(&config.web_search, &query));
             }
 
-            let _ = search_coord_tx.send(CoordinatorMessage::SearchMessage(SearchMessageData {
-                results,
-            }));
-        }
-        else => {
-            info!("Search channel closed, exiting listener.");
-            break;
+            for result in results.iter_mut() {
+                result.launch = launch.clone();
+            }
         }
-    }}
+
+        let _ = search_coord_tx.send(CoordinatorMessage::SearchMessage(SearchMessageData {
+            results,
+            query: Some(msg),
+        }));
+    }
     info!("Search listener exited.");
 }
+
+/// Newest query waiting on the channel, or `msg` when there is none
+fn latest_query(msg: String, search_rx: &mut mpsc::UnboundedReceiver<String>) -> String {
+    let mut latest = msg;
+    while let Ok(newer) = search_rx.try_recv() {
+        latest = newer;
+    }
+    latest
+}
+
+/// Names of connected monitors, only asked from Hyprland when the query has `@` tokens
+async fn connected_monitors(query: &str) -> Vec<MonitorName> {
+    if !query.split(' ').any(|word| word.len() > 1 && word.starts_with('@')) {
+        return Vec::new();
+    }
+    match tokio::task::spawn_blocking(get_monitors).await {
+        Ok(Ok(monitors)) => monitors.into_iter().map(|m| m.name).collect(),
+        _ => {
+            error!("Failed to list monitors for launch options.");
+            Vec::new()
+        },
+    }
+}
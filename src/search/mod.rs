@@ -1,7 +1,10 @@
-mod matcher_helpers;
-
+pub mod arg_prompt;
 pub mod entity_loader;
 pub mod entity_model;
 pub mod entity_repository;
 pub mod error;
+pub mod hypr_commands;
+pub mod launch_options;
 pub mod listener;
+pub mod matcher_helpers;
+pub mod script_meta;
@@ -0,0 +1,111 @@
+use std::{fs::File, io::Read, path::Path};
+
+/// Only the start of a script is read for its header
+const HEADER_BYTES: u64 = 4096;
+const KEY_PREFIX: &str = "shunpo-";
+
+/// Metadata declared in a script's leading comment block:
+///
+/// ```text
+/// #!/bin/sh
+/// # shunpo-name: Pull all repos
+/// # shunpo-description: git pull in every repository under ~/src
+/// # shunpo-keywords: git, sync
+/// # shunpo-mode: background
+/// # shunpo-arg: branch
+/// # shunpo-arg: remote = origin|upstream
+/// # shunpo-icon: folder-download
+/// ```
+///
+/// `#`, `//` and `--` comments are recognized. Unknown keys are ignored.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScriptMeta {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    pub mode: ScriptMode,
+    /// Arguments the script expects, in order
+    pub args: Vec<ScriptArg>,
+    /// Icon name from the icon theme
+    pub icon: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ScriptMode {
+    /// In a new terminal window
+    #[default]
+    Terminal,
+    /// Without a window
+    Background,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptArg {
+    pub name: String,
+    /// Allowed values, any value when empty
+    pub choices: Vec<String>,
+}
+
+/// Read the header of a script, None if it has no `shunpo-` keys or can't be read
+pub fn read_script_meta(path: &Path) -> Option<ScriptMeta> {
+    let mut head = Vec::new();
+    File::open(path).ok()?
+        .take(HEADER_BYTES)
+        .read_to_end(&mut head).ok()?;
+    parse_script_meta(&String::from_utf8_lossy(&head))
+}
+
+/// Parse the leading comment block of a script
+pub fn parse_script_meta(content: &str) -> Option<ScriptMeta> {
+    let mut meta = ScriptMeta::default();
+    let mut found = false;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with("#!") {
+            continue;
+        }
+        let Some(comment) = strip_comment(line) else {
+            break; // end of the header
+        };
+        let Some((key, value)) = comment.strip_prefix(KEY_PREFIX).and_then(|kv| kv.split_once(':')) else {
+            continue;
+        };
+        let value = value.trim();
+        found = true;
+
+        match key.trim() {
+            "name" => meta.name = non_empty(value),
+            "description" => meta.description = non_empty(value),
+            "keywords" => meta.keywords.extend(split_list(value, ',')),
+            "mode" => meta.mode = if value == "background" { ScriptMode::Background } else { ScriptMode::Terminal },
+            "arg" => meta.args.extend(parse_arg(value)),
+            "icon" => meta.icon = non_empty(value),
+            _ => {},
+        }
+    }
+
+    if found { Some(meta) } else { None }
+}
+
+/// `name` or `name = a|b|c`
+fn parse_arg(value: &str) -> Option<ScriptArg> {
+    let (name, choices) = match value.split_once('=') {
+        Some((name, choices)) => (name.trim(), split_list(choices, '|')),
+        None => (value, Vec::new()),
+    };
+    non_empty(name).map(|name| ScriptArg { name, choices })
+}
+
+fn strip_comment(line: &str) -> Option<&str> {
+    ["#", "//", "--"].iter()
+        .find_map(|prefix| line.strip_prefix(prefix))
+        .map(str::trim)
+}
+
+fn split_list(value: &str, separator: char) -> Vec<String> {
+    value.split(separator).filter_map(|v| non_empty(v.trim())).collect()
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    if value.is_empty() { None } else { Some(value.to_string()) }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::VecDeque,
+    io::{BufRead, BufReader, Read},
+    os::unix::process::CommandExt,
+    path::Path,
+    process::{Child, Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use tokio::sync::{mpsc, oneshot};
+
+use crate::system::{launch::{signal_group, SIGKILL, SIGTERM}, launch_env::LaunchEnv};
+
+/// Lines of output kept for display, older lines are dropped
+pub const OUTPUT_LINES: usize = 200;
+/// How often a running job is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Output still arriving after exit, e.g. from processes the job started, is cut off after this
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(200);
+/// Time a cancelled job has to exit after SIGTERM before its processes are killed
+const KILL_GRACE: Duration = Duration::from_secs(1);
+
+/// How a background job ended
+#[derive(Debug, PartialEq)]
+pub enum JobStatus {
+    Exited(Option<i32>),
+    Cancelled,
+    TimedOut,
+    Failed(String),
+}
+impl std::fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Exited(Some(0)) => write!(f, "done"),
+            Self::Exited(Some(code)) => write!(f, "exited with status {}", code),
+            Self::Exited(None) => write!(f, "killed by signal"),
+            Self::Cancelled => write!(f, "cancelled"),
+            Self::TimedOut => write!(f, "timed out"),
+            Self::Failed(e) => write!(f, "failed to start: {}", e),
+        }
+    }
+}
+
+/// The running background job, cancelled by sending on `cancel`
+pub struct JobHandle {
+    pub name: String,
+    cancel: oneshot::Sender<()>,
+}
+impl JobHandle {
+    pub fn new(name: String) -> (Self, oneshot::Receiver<()>) {
+        let (cancel, cancelled) = oneshot::channel();
+        (JobHandle { name, cancel }, cancelled)
+    }
+    pub fn is_running(&self) -> bool {
+        !self.cancel.is_closed()
+    }
+    pub fn cancel(self) {
+        let _ = self.cancel.send(());
+    }
+}
+
+/// Most recent output lines of a job
+#[derive(Default)]
+pub struct JobOutput {
+    lines: VecDeque<String>,
+}
+impl JobOutput {
+    pub fn push(&mut self, line: String) {
+        if self.lines.len() == OUTPUT_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+    pub fn lines(&self) -> impl Iterator<Item = &String> {
+        self.lines.iter()
+    }
+}
+
+/// Run `call` with `sh -c` without a window, passing each line of stdout and stderr to `on_line`.
+/// The job runs in its own process group, which is killed when `cancel` fires or after `timeout`.
+pub async fn run_job(
+    call: &str,
+    env: &LaunchEnv,
+    cwd: Option<&Path>,
+    timeout: Duration,
+    mut cancel: oneshot::Receiver<()>,
+    mut on_line: impl FnMut(String),
+) -> JobStatus {
+    let mut command = Command::new("sh");
+    env.apply(&mut command);
+    if let Some(dir) = cwd {
+        command.current_dir(dir);
+    }
+    // processes the script starts are stopped with it
+    command.process_group(0);
+    let spawned = command
+        .arg("-c")
+        .arg(call)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => return JobStatus::Failed(e.to_string()),
+    };
+
+    let (line_tx, mut line_rx) = mpsc::unbounded_channel::<String>();
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, line_tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, line_tx);
+    }
+
+    let deadline = tokio::time::sleep(timeout);
+    tokio::pin!(deadline);
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        tokio::select! {
+            Some(line) = line_rx.recv() => on_line(line),
+            _ = &mut cancel => {
+                kill(&mut child).await;
+                return JobStatus::Cancelled;
+            },
+            _ = &mut deadline => {
+                kill(&mut child).await;
+                return JobStatus::TimedOut;
+            },
+            _ = poll.tick() => {
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        let _ = tokio::time::timeout(DRAIN_TIMEOUT, async {
+                            while let Some(line) = line_rx.recv().await {
+                                on_line(line);
+                            }
+                        }).await;
+                        return JobStatus::Exited(status.code());
+                    },
+                    Ok(None) => {},
+                    Err(e) => {
+                        kill(&mut child).await;
+                        return JobStatus::Failed(e.to_string());
+                    },
+                }
+            },
+        }
+    }
+}
+
+/// Read lines on a thread, the pipes are blocking
+fn forward_lines(pipe: impl Read + Send + 'static, line_tx: mpsc::UnboundedSender<String>) {
+    thread::spawn(move || {
+        for line in BufReader::new(pipe).lines() {
+            let Ok(line) = line else { break };
+            if line_tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// SIGTERM to the job's process group, then SIGKILL to what is left of it after `KILL_GRACE`
+async fn kill(child: &mut Child) {
+    signal_group(child, SIGTERM);
+    let deadline = tokio::time::Instant::now() + KILL_GRACE;
+    while tokio::time::Instant::now() < deadline {
+        if !matches!(child.try_wait(), Ok(None)) {
+            break;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    signal_group(child, SIGKILL);
+    let _ = child.wait();
+}
@@ -0,0 +1,108 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Command, Stdio},
+    thread,
+};
+
+use log::{error, info, warn};
+
+use crate::system::{error::LaunchError, launch_env::LaunchEnv};
+
+/// Run by `wl-paste --watch` on every clipboard change. Prints the clipboard state (wl-clipboard 2.2+),
+/// and stops the watcher once Shunpo exited and nobody reads its output.
+const WATCH_SCRIPT: &str = "trap '' PIPE; echo \"${CLIPBOARD_STATE:-data}\" || kill $PPID";
+/// Offered by password managers (KeePassXC, KDE) with copied secrets, never stored
+pub const PASSWORD_HINT_TYPE: &str = "x-kde-passwordManagerHint";
+
+/// Put `text` on the Wayland clipboard with `wl-copy`.
+/// wl-copy keeps serving the clipboard from a forked process, so only the parent is waited for.
+pub fn copy_to_clipboard(text: &str) -> Result<(), LaunchError> {
+    let mut command = Command::new("wl-copy");
+    LaunchEnv::original().apply(&mut command);
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(LaunchError::Spawn)?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).map_err(LaunchError::Spawn)?;
+    }
+    let status = child.wait().map_err(LaunchError::Spawn)?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(LaunchError::Exited { code: status.code(), stderr: String::new() })
+    }
+}
+
+/// Call `on_text` on a thread with the text of every copy, unless it offers one of the `excluded` types.
+pub fn watch_clipboard(excluded: Vec<String>, mut on_text: impl FnMut(String) + Send + 'static) {
+    thread::spawn(move || {
+        let mut command = Command::new("wl-paste");
+        LaunchEnv::original().apply(&mut command);
+        let spawned = command
+            .args(["--watch", "sh", "-c", WATCH_SCRIPT])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                error!("Clipboard history disabled, failed to start wl-paste: {}", e);
+                return;
+            },
+        };
+        info!("Watching the clipboard.");
+
+        let Some(stdout) = child.stdout.take() else { return };
+        for state in BufReader::new(stdout).lines().map_while(Result::ok) {
+            // `sensitive` is set by password managers, `nil` and `clear` carry no data
+            if state.trim() != "data" {
+                continue;
+            }
+            let types = clipboard_types();
+            if !should_store(&types, &excluded) {
+                continue;
+            }
+            match paste_text() {
+                Some(text) if !text.trim().is_empty() => on_text(text),
+                _ => {},
+            }
+        }
+
+        let _ = child.wait();
+        warn!("Clipboard watcher exited.");
+    });
+}
+
+/// Text copies which don't offer an excluded type or the password manager hint
+pub fn should_store(types: &[String], excluded: &[String]) -> bool {
+    let has_text = types.iter().any(|t| t.starts_with("text/") || t == "UTF8_STRING" || t == "STRING");
+    let is_excluded = types.iter().any(|t| {
+        t.eq_ignore_ascii_case(PASSWORD_HINT_TYPE) || excluded.iter().any(|e| e.eq_ignore_ascii_case(t))
+    });
+    has_text && !is_excluded
+}
+
+fn clipboard_types() -> Vec<String> {
+    wl_paste(&["--list-types"])
+        .map(|types| types.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn paste_text() -> Option<String> {
+    wl_paste(&["--no-newline", "--type", "text"])
+}
+
+fn wl_paste(args: &[&str]) -> Option<String> {
+    let mut command = Command::new("wl-paste");
+    LaunchEnv::original().apply(&mut command);
+    let output = command.args(args).stdin(Stdio::null()).stderr(Stdio::null()).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
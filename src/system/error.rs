@@ -0,0 +1,22 @@
+pub enum LaunchError {
+    Spawn(std::io::Error),
+    Exited { code: Option<i32>, stderr: String },
+    NotFound(String),
+    NoWindow,
+    JobRunning,
+}
+impl std::fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Spawn(e) => write!(f, "failed to start: {}", e),
+            Self::Exited { code: Some(code), stderr } if !stderr.is_empty() => {
+                write!(f, "exited with status {}: {}", code, stderr)
+            },
+            Self::Exited { code: Some(code), .. } => write!(f, "exited with status {}", code),
+            Self::Exited { code: None, .. } => write!(f, "killed by signal"),
+            Self::NotFound(program) => write!(f, "command not found: {}", program),
+            Self::NoWindow => write!(f, "no window appeared"),
+            Self::JobRunning => write!(f, "still running (Ctrl+C to cancel)"),
+        }
+    }
+}
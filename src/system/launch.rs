@@ -0,0 +1,131 @@
+use std::{
+    io::Read,
+    path::Path,
+    process::{Child, Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::system::{error::LaunchError, launch_env::LaunchEnv, terminal::find_in_path};
+
+/// How long a command may run before it is considered started successfully.
+pub const SHORT_LIVED_GRACE: Duration = Duration::from_millis(1500);
+/// Builtins which may start a command, these aren't looked up in `PATH`
+const SHELL_BUILTINS: &[&str] = &["exec", "command", "cd", ".", "source", "eval", "export", "set", "test", "["];
+
+/// Run `call` with `sh -c`, waiting up to `grace` for it to fail.
+/// Commands still running after `grace` are left running and reaped in the background.
+/// Blocking, call from a blocking task.
+pub fn spawn_tracked(call: &str, grace: Duration, env: &LaunchEnv) -> Result<(), LaunchError> {
+    let mut command = Command::new("sh");
+    env.apply(&mut command);
+    let mut child = command
+        .arg("-c")
+        .arg(call)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(LaunchError::Spawn)?;
+
+    // drain stderr on its own thread, so a chatty child never blocks on a full pipe
+    let (stderr_tx, stderr_rx) = mpsc::channel::<String>();
+    if let Some(mut stderr) = child.stderr.take() {
+        thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stderr.read_to_string(&mut output);
+            let _ = stderr_tx.send(output);
+        });
+    }
+
+    let deadline = Instant::now() + grace;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => {
+                // background processes started by the command may keep stderr open
+                let stderr = stderr_rx.recv_timeout(Duration::from_millis(200))
+                    .unwrap_or_default();
+                return Err(LaunchError::Exited {
+                    code: status.code(),
+                    stderr: last_line(&stderr),
+                });
+            },
+            Ok(None) if Instant::now() < deadline => {
+                thread::sleep(Duration::from_millis(50));
+            },
+            Ok(None) => {
+                thread::spawn(move || { let _ = child.wait(); });
+                return Ok(());
+            },
+            Err(e) => return Err(LaunchError::Spawn(e)),
+        }
+    }
+}
+
+/// Program a simple command runs, skipping `env` and `VAR=value` prefixes.
+/// None when the shell would have to interpret the command to know it.
+pub fn command_program(cmd: &str) -> Option<&str> {
+    if cmd.contains(['$', '`', ';', '|', '&', '<', '>', '(', ')', '{', '}', '\\', '*', '?', '~']) {
+        return None;
+    }
+    let word = cmd.split_whitespace()
+        .find(|w| *w != "env" && !w.contains('='))?;
+    let unquoted = word.strip_prefix('\'').and_then(|w| w.strip_suffix('\''))
+        .or_else(|| word.strip_prefix('"').and_then(|w| w.strip_suffix('"')))
+        .unwrap_or(word);
+    // quoted words containing spaces were split above
+    if unquoted.is_empty() || unquoted.starts_with('-') || unquoted.contains(['\'', '"'])
+        || SHELL_BUILTINS.contains(&unquoted) {
+        return None;
+    }
+    Some(unquoted)
+}
+
+/// Program of `cmd` when it can't be found, checked before dispatching
+/// so that a typo fails right away instead of through Hyprland.
+pub fn missing_program(cmd: &str, env: &LaunchEnv) -> Option<String> {
+    let program = command_program(cmd)?;
+    let found = if program.contains('/') {
+        Path::new(program).is_file()
+    } else {
+        let path_env = env.vars().get("PATH").cloned().unwrap_or_default();
+        find_in_path(program, &path_env).is_some()
+    };
+    (!found).then(|| program.to_string())
+}
+
+/// Last non-empty line of command output, usually the most relevant error message
+fn last_line(output: &str) -> String {
+    output.lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("")
+        .to_string()
+}
+
+pub const SIGKILL: i32 = 9;
+pub const SIGTERM: i32 = 15;
+
+// from the C library std already links against
+unsafe extern "C" {
+    fn killpg(pgrp: i32, sig: i32) -> i32;
+}
+
+/// Signal every process in the group led by `child`, the child alone if the group can't be signalled.
+/// The child must have been spawned with `process_group(0)`.
+pub fn signal_group(child: &mut Child, signal: i32) {
+    // NOTE: UNSAFE
+    // plain syscall wrapper, the group id is the child's pid as it leads its own group
+    let sent = unsafe { killpg(child.id() as i32, signal) };
+    if sent != 0 {
+        let _ = child.kill();
+    }
+}
+
+/// Quote a string as a single POSIX shell word
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
@@ -0,0 +1,79 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    process::Command,
+    sync::OnceLock,
+};
+
+use crate::system::launch::shell_quote;
+
+/// Variables that must not leak into launched apps: Shunpo's own settings,
+/// and activation tokens meant for Shunpo which would make apps steal focus.
+const INTERNAL_VARS: &[&str] = &["DESKTOP_STARTUP_ID", "XDG_ACTIVATION_TOKEN"];
+const INTERNAL_PREFIX: &str = "SHUNPO_";
+
+/// Environment of the process at startup, before Shunpo changed it for itself.
+static ORIGINAL_ENV: OnceLock<BTreeMap<String, String>> = OnceLock::new();
+
+/// Capture the environment. Call at the start of main, before any `set_var`.
+/// Variables which are not valid unicode are not passed on to launched apps.
+pub fn capture_original_env() {
+    ORIGINAL_ENV.get_or_init(|| unicode_vars().collect());
+}
+
+/// Environment for a launched app: the original environment without internal
+/// variables, plus per-app overrides.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LaunchEnv {
+    base: BTreeMap<String, String>,
+    overrides: BTreeMap<String, String>,
+}
+impl LaunchEnv {
+    /// From the environment captured at startup, or the current one if it was never captured
+    pub fn original() -> Self {
+        match ORIGINAL_ENV.get() {
+            Some(vars) => Self::new(vars.clone()),
+            None => Self::new(unicode_vars()),
+        }
+    }
+    pub fn new(base: impl IntoIterator<Item = (String, String)>) -> Self {
+        LaunchEnv {
+            base: base.into_iter().filter(|(k, _)| !is_internal(k)).collect(),
+            overrides: BTreeMap::new(),
+        }
+    }
+    pub fn with_overrides(mut self, overrides: &HashMap<String, String>) -> Self {
+        self.overrides.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+        self
+    }
+    /// All variables an app is started with
+    pub fn vars(&self) -> BTreeMap<String, String> {
+        let mut vars = self.base.clone();
+        vars.extend(self.overrides.clone());
+        vars
+    }
+    /// Replace the environment of a command spawned by Shunpo
+    pub fn apply(&self, command: &mut Command) {
+        command.env_clear().envs(self.vars());
+    }
+    /// Prefix a command executed by Hyprland with the overrides: `env K='V' cmd`.
+    /// Hyprland starts it with the compositor's environment, which Shunpo never modified.
+    pub fn wrap_exec(&self, cmd: &str) -> String {
+        if self.overrides.is_empty() {
+            return cmd.to_string();
+        }
+        let assignments = self.overrides.iter()
+            .map(|(k, v)| format!("{}={}", k, shell_quote(v)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        format!("env {} {}", assignments, cmd)
+    }
+}
+
+fn unicode_vars() -> impl Iterator<Item = (String, String)> {
+    env::vars_os().filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?)))
+}
+
+fn is_internal(key: &str) -> bool {
+    INTERNAL_VARS.contains(&key) || key.starts_with(INTERNAL_PREFIX)
+}
@@ -1 +1,9 @@
+pub mod background;
+pub mod clipboard;
+pub mod error;
+pub mod launch;
+pub mod launch_env;
+pub mod paths;
+pub mod systemd;
+pub mod terminal;
 pub mod volume;
@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+/// `$XDG_DATA_HOME`, or `~/.local/share`
+pub fn data_home() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+}
+
+/// Directory for files Shunpo keeps between runs
+pub fn shunpo_data_dir() -> Option<PathBuf> {
+    data_home().map(|dir| dir.join("shunpo"))
+}
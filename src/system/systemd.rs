@@ -0,0 +1,53 @@
+use std::{
+    process,
+    sync::{atomic::{AtomicU32, Ordering}, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::system::launch::shell_quote;
+
+/// Launcher name in app unit names: `app-<launcher>-<id>-<suffix>.scope`
+const LAUNCHER: &str = "shunpo";
+
+static LAUNCHES: AtomicU32 = AtomicU32::new(0);
+static STARTED: OnceLock<u32> = OnceLock::new();
+
+/// Transient scope unit for one launch of an app, named after the freedesktop
+/// app unit convention so desktop tools can attribute it to the app.
+pub fn app_scope_unit(app_id: &str, suffix: u64) -> String {
+    format!("app-{}-{}-{:x}.scope", LAUNCHER, escape_unit_part(app_id), suffix)
+}
+
+/// Last part of a unit name, unique per launch: the launcher pid and a launch counter.
+/// The counter starts at the launcher's start time, so a restart reusing the pid
+/// doesn't reuse the names of scopes still running.
+pub fn unit_suffix() -> u64 {
+    let started = *STARTED.get_or_init(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as u32).unwrap_or_default()
+    });
+    let launch = started.wrapping_add(LAUNCHES.fetch_add(1, Ordering::Relaxed));
+    (u64::from(process::id()) << 32) | u64::from(launch)
+}
+
+/// Escape a string for use in a unit name, as `systemd-escape` does.
+/// `-` separates the parts of app unit names, so it is escaped as well.
+pub fn escape_unit_part(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for (i, byte) in s.bytes().enumerate() {
+        match byte {
+            b'.' if i == 0 => escaped.push_str("\\x2e"),
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b':' | b'_' | b'.' => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    escaped
+}
+
+/// Run `cmd` in its own user scope. The command keeps its pid, so exec rules still apply.
+pub fn in_scope(cmd: &str, unit: &str) -> String {
+    format!(
+        "systemd-run --user --scope --quiet --collect --unit={} -- sh -c {}",
+        shell_quote(unit),
+        shell_quote(cmd),
+    )
+}
@@ -0,0 +1,182 @@
+use std::{fs, os::unix::fs::PermissionsExt, path::{Path, PathBuf}};
+
+use crate::system::launch::shell_quote;
+
+/// Command line flags of a terminal emulator. `{}` is replaced with the quoted value.
+pub struct TerminalTemplate {
+    pub name: &'static str,
+    /// Arguments before any option, e.g. a subcommand
+    pub args: &'static str,
+    pub cwd: Option<&'static str>,
+    /// Set with an escape sequence when missing
+    pub title: Option<&'static str>,
+    /// Dropped when missing, konsole has no flag for it
+    pub class: Option<&'static str>,
+    /// Keep the window open after the command exits
+    pub hold: Option<&'static str>,
+    /// Separates the options from the command to run
+    pub exec: &'static str,
+}
+
+/// Known terminals, in order of preference when detecting one
+pub const TERMINALS: &[TerminalTemplate] = &[
+    TerminalTemplate {
+        name: "ghostty",
+        args: "",
+        cwd: Some("--working-directory={}"),
+        title: Some("--title={}"),
+        class: Some("--class={}"),
+        hold: Some("--wait-after-command=true"),
+        exec: "-e",
+    },
+    TerminalTemplate {
+        name: "kitty",
+        args: "",
+        cwd: Some("--directory {}"),
+        title: Some("--title {}"),
+        class: Some("--class {}"),
+        hold: Some("--hold"),
+        exec: "",
+    },
+    TerminalTemplate {
+        name: "alacritty",
+        args: "",
+        cwd: Some("--working-directory {}"),
+        title: Some("--title {}"),
+        class: Some("--class {}"),
+        hold: Some("--hold"),
+        exec: "-e",
+    },
+    TerminalTemplate {
+        name: "foot",
+        args: "",
+        cwd: Some("--working-directory={}"),
+        title: Some("--title={}"),
+        class: Some("--app-id={}"),
+        hold: Some("--hold"),
+        exec: "",
+    },
+    TerminalTemplate {
+        name: "wezterm",
+        args: "start",
+        cwd: Some("--cwd {}"),
+        title: None,
+        class: Some("--class {}"),
+        hold: None,
+        exec: "--",
+    },
+    TerminalTemplate {
+        name: "konsole",
+        args: "",
+        cwd: Some("--workdir {}"),
+        title: None,
+        class: None,
+        hold: Some("--hold"),
+        exec: "-e",
+    },
+    TerminalTemplate {
+        name: "xterm",
+        args: "",
+        cwd: None,
+        title: Some("-T {}"),
+        class: Some("-class {}"),
+        hold: Some("-hold"),
+        exec: "-e",
+    },
+];
+
+/// Used for terminals not in the registry
+const GENERIC: TerminalTemplate = TerminalTemplate {
+    name: "generic",
+    args: "",
+    cwd: None,
+    title: None,
+    class: None,
+    hold: None,
+    exec: "-e",
+};
+
+/// A command to run in a new terminal window
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TerminalCommand {
+    pub cmd: String,
+    pub cwd: Option<PathBuf>,
+    pub title: Option<String>,
+    pub class: Option<String>,
+    pub hold: bool,
+}
+impl TerminalCommand {
+    pub fn new(cmd: &str) -> Self {
+        TerminalCommand { cmd: cmd.to_string(), ..Default::default() }
+    }
+}
+
+/// Template for a terminal, by executable name or path, which may be followed by arguments
+pub fn template_for(terminal: &str) -> &'static TerminalTemplate {
+    let program = terminal.split_whitespace().next().unwrap_or_default();
+    let name = Path::new(program)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    TERMINALS.iter()
+        .find(|t| t.name == name)
+        .unwrap_or(&GENERIC)
+}
+
+/// Shell command opening `terminal` running `command` with `sh -c`.
+/// Options the terminal has no flag for are emulated in the shell where possible.
+pub fn compose_terminal(terminal: &str, command: &TerminalCommand) -> String {
+    let template = template_for(terminal);
+    let mut parts = vec![terminal.to_string()];
+    let mut cmd = command.cmd.clone();
+
+    if !template.args.is_empty() {
+        parts.push(template.args.to_string());
+    }
+    if let Some(cwd) = &command.cwd {
+        let cwd = cwd.to_string_lossy();
+        match template.cwd {
+            Some(flag) => parts.push(flag.replace("{}", &shell_quote(&cwd))),
+            None => cmd = format!("cd {} && {}", shell_quote(&cwd), cmd),
+        }
+    }
+    if let Some(title) = &command.title {
+        match template.title {
+            Some(flag) => parts.push(flag.replace("{}", &shell_quote(title))),
+            None => cmd = format!("printf '\\033]2;%s\\007' {}; {}", shell_quote(title), cmd),
+        }
+    }
+    // a window class can't be set from inside the terminal, it is left out
+    if let (Some(class), Some(flag)) = (&command.class, template.class) {
+        parts.push(flag.replace("{}", &shell_quote(class)));
+    }
+    if command.hold {
+        match template.hold {
+            Some(flag) => parts.push(flag.to_string()),
+            None => cmd = format!("{}; printf '\\n[exited, press enter to close]'; read -r _", cmd),
+        }
+    }
+    if !template.exec.is_empty() {
+        parts.push(template.exec.to_string());
+    }
+    parts.push(format!("sh -c {}", shell_quote(&cmd)));
+    parts.join(" ")
+}
+
+/// First known terminal found in `path_env`
+pub fn detect_terminal(path_env: &str) -> Option<PathBuf> {
+    TERMINALS.iter().find_map(|t| find_in_path(t.name, path_env))
+}
+
+/// Full path of an executable in a `PATH`-style list of directories
+pub fn find_in_path(name: &str, path_env: &str) -> Option<PathBuf> {
+    path_env
+        .split(':')
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| Path::new(dir).join(name))
+        .find(|path| {
+            path.is_file() && fs::metadata(path)
+                .map(|m| m.permissions().mode() & 0o111 != 0)
+                .unwrap_or(false)
+        })
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use nucleo::{Config, Matcher};
+
+use crate::{
+    coordinator::types::SearchMessageData,
+    search::{
+        arg_prompt::{split_words, ArgPrompt, PromptStep},
+        entity_loader::script_entity,
+        entity_model::LauncherEntity,
+        launch_options::append_args,
+    },
+};
+
+fn pull_repos() -> LauncherEntity {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests/fixtures/scripts/pull_repos.sh");
+    LauncherEntity::from_executable(&script_entity(path, "pull_repos.sh".into()))
+}
+
+fn done(step: PromptStep) -> LauncherEntity {
+    match step {
+        PromptStep::Done(entity) => *entity,
+        PromptStep::Next => panic!("expected all arguments to be collected"),
+    }
+}
+
+#[test]
+fn declared_arguments_are_prompted_in_order() {
+    let mut prompt = ArgPrompt::declared(&pull_repos()).expect("script declares arguments");
+    assert_eq!(prompt.label(), "branch (1/2)");
+    assert!(!prompt.has_choices());
+
+    assert!(matches!(prompt.submit("main"), PromptStep::Next));
+    assert_eq!(prompt.label(), "remote (2/2)");
+    assert!(prompt.has_choices());
+
+    let entity = done(prompt.submit("upstream"));
+    assert_eq!(entity.launch.args, vec!["main".to_string(), "upstream".to_string()]);
+}
+
+#[test]
+fn choices_are_fuzzy_completed() {
+    let mut matcher = Matcher::new(Config::DEFAULT);
+    let mut prompt = ArgPrompt::declared(&pull_repos()).unwrap();
+    assert!(prompt.complete("", &mut matcher).is_empty());
+
+    let _ = prompt.submit("main");
+    assert_eq!(prompt.complete("", &mut matcher), vec!["origin".to_string(), "upstream".to_string()]);
+    assert_eq!(prompt.complete("ups", &mut matcher), vec!["upstream".to_string()]);
+    assert!(prompt.complete("xyz", &mut matcher).is_empty());
+}
+
+#[test]
+fn search_results_arriving_during_the_prompt_are_dropped() {
+    // `pull` was typed, Tab started the prompt before its search results arrived
+    let late = SearchMessageData { results: vec![pull_repos()], query: Some("pull".into()) };
+    let choices = SearchMessageData::composed(vec![]);
+
+    assert!(!late.is_current("", true));
+    assert!(choices.is_current("", true));
+    // results of an earlier query are dropped too
+    assert!(!late.is_current("pull_r", false));
+    assert!(late.is_current("pull", false));
+}
+
+#[test]
+fn free_form_arguments_are_split_on_whitespace() {
+    let mut prompt = ArgPrompt::free_form(&pull_repos());
+    assert_eq!(prompt.label(), "arguments");
+
+    let entity = done(prompt.submit("  --depth 1 "));
+    assert_eq!(entity.launch.args, vec!["--depth".to_string(), "1".to_string()]);
+}
+
+#[test]
+fn quoted_free_form_arguments_stay_whole() {
+    let entity = done(ArgPrompt::free_form(&pull_repos()).submit("\"my file.txt\" -m 'fix: a  b' it\\'s \"\""));
+    assert_eq!(entity.launch.args, vec!["my file.txt", "-m", "fix: a  b", "it's", ""]);
+    assert_eq!(append_args("'/s/x.sh'", &entity.launch.args), "'/s/x.sh' 'my file.txt' '-m' 'fix: a  b' 'it'\\''s' ''");
+
+    assert_eq!(split_words("a\\ b \"c \\\" d\" 'e\\f"), vec!["a b", "c \" d", "e\\f"]);
+    // declared parameters take the value as it was typed
+    let mut prompt = ArgPrompt::declared(&pull_repos()).unwrap();
+    let _ = prompt.submit("my branch");
+    assert_eq!(done(prompt.submit("origin")).launch.args, vec!["my branch", "origin"]);
+}
+
+#[test]
+fn arguments_are_quoted() {
+    assert_eq!(append_args("'/s/x.sh'", &[]), "'/s/x.sh'");
+    assert_eq!(
+        append_args("'/s/x.sh'", &["a b".into(), "it's; rm -rf ~".into()]),
+        "'/s/x.sh' 'a b' 'it'\\''s; rm -rf ~'",
+    );
+}
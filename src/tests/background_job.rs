@@ -0,0 +1,117 @@
+use std::time::{Duration, Instant};
+
+use tokio::sync::oneshot;
+
+use crate::system::{background::{run_job, JobHandle, JobOutput, JobStatus, OUTPUT_LINES}, launch_env::LaunchEnv};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+fn env() -> LaunchEnv {
+    LaunchEnv::new(vec![("PATH".to_string(), "/usr/bin:/bin".to_string())])
+}
+
+async fn run(call: &str, timeout: Duration, cancel: oneshot::Receiver<()>) -> (JobStatus, Vec<String>) {
+    let mut lines = Vec::new();
+    let status = run_job(call, &env(), None, timeout, cancel, |line| lines.push(line)).await;
+    (status, lines)
+}
+
+#[tokio::test]
+async fn captures_stdout_and_stderr() {
+    let (_cancel, cancelled) = oneshot::channel();
+    let (status, mut lines) = run("echo one; echo two >&2; echo three", TIMEOUT, cancelled).await;
+
+    assert_eq!(status, JobStatus::Exited(Some(0)));
+    lines.sort();
+    assert_eq!(lines, vec!["one", "three", "two"]);
+}
+
+#[tokio::test]
+async fn reports_exit_status() {
+    let (_cancel, cancelled) = oneshot::channel();
+    let (status, _) = run("exit 3", TIMEOUT, cancelled).await;
+
+    assert_eq!(status, JobStatus::Exited(Some(3)));
+    assert_eq!(status.to_string(), "exited with status 3");
+}
+
+#[tokio::test]
+async fn runs_in_working_directory() {
+    let (_cancel, cancelled) = oneshot::channel();
+    let mut lines = Vec::new();
+    let status = run_job("pwd", &env(), Some(std::path::Path::new("/")), TIMEOUT, cancelled, |line| lines.push(line)).await;
+
+    assert_eq!(status, JobStatus::Exited(Some(0)));
+    assert_eq!(lines, vec!["/"]);
+}
+
+#[tokio::test]
+async fn cancel_kills_job() {
+    let (handle, cancelled) = JobHandle::new("sleeper".to_string());
+    assert!(handle.is_running());
+
+    let started = Instant::now();
+    let job = tokio::spawn(async move { run("echo started; sleep 30", TIMEOUT, cancelled).await });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    handle.cancel();
+    let (status, lines) = job.await.unwrap();
+
+    assert_eq!(status, JobStatus::Cancelled);
+    assert_eq!(lines, vec!["started"]);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn cancel_kills_processes_started_by_the_job() {
+    let (handle, cancelled) = JobHandle::new("waiter".to_string());
+
+    let started = Instant::now();
+    let job = tokio::spawn(async move { run("sleep 30 & echo $!; wait", TIMEOUT, cancelled).await });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    handle.cancel();
+    let (status, lines) = job.await.unwrap();
+
+    assert_eq!(status, JobStatus::Cancelled);
+    assert!(started.elapsed() < Duration::from_secs(5));
+    // the backgrounded sleep is gone too, at most waiting to be reaped
+    let stat_path = format!("/proc/{}/stat", lines[0]);
+    let exited = || {
+        let stat = std::fs::read_to_string(&stat_path).unwrap_or_default();
+        stat.is_empty() || stat.contains(") Z ")
+    };
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while !exited() && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(exited(), "sleep still running");
+}
+
+#[tokio::test]
+async fn handle_stops_running_when_job_ends() {
+    let (handle, cancelled) = JobHandle::new("quick".to_string());
+    run("true", TIMEOUT, cancelled).await;
+
+    assert!(!handle.is_running());
+}
+
+#[tokio::test]
+async fn times_out() {
+    let (_cancel, cancelled) = oneshot::channel();
+    let started = Instant::now();
+    let (status, _) = run("sleep 30", Duration::from_millis(200), cancelled).await;
+
+    assert_eq!(status, JobStatus::TimedOut);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn output_keeps_latest_lines() {
+    let mut output = JobOutput::default();
+    for i in 0..OUTPUT_LINES + 5 {
+        output.push(i.to_string());
+    }
+
+    let lines: Vec<&String> = output.lines().collect();
+    assert_eq!(lines.len(), OUTPUT_LINES);
+    assert_eq!(lines[0], "5");
+}
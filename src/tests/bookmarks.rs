@@ -0,0 +1,106 @@
+use std::path::Path;
+
+use nucleo::Matcher;
+
+use crate::{
+    providers::{
+        bookmarks::{bookmark_entity, bookmark_lookup, parse_chromium, parse_sqlite_rows, read_bookmark_file, url_lookup, Bookmark},
+        url::as_url,
+    },
+    search::entity_model::{Dispatcher, Export, FileEntity, LauncherEntity},
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/fixtures/bookmarks");
+
+fn fixture(name: &str) -> Vec<Bookmark> {
+    read_bookmark_file(&Path::new(FIXTURES).join(name)).unwrap()
+}
+
+fn titles(results: &[LauncherEntity]) -> Vec<String> {
+    results.iter().map(|r| r.file_entity.ui_name().clone()).collect()
+}
+
+#[test]
+fn toml_file_is_read() {
+    let bookmarks = fixture("bookmarks.toml");
+    assert_eq!(bookmarks.len(), 2);
+    assert_eq!(bookmarks[0].title, "Hyprland wiki");
+    assert_eq!(bookmarks[0].tags, vec!["docs", "wm"]);
+    assert_eq!(bookmarks[1].title, "");
+    assert_eq!(bookmarks[1].url, "https://docs.rs");
+}
+
+#[test]
+fn markdown_links_and_bare_urls_are_read() {
+    let bookmarks = fixture("bookmarks.md");
+    let urls: Vec<&str> = bookmarks.iter().map(|b| b.url.as_str()).collect();
+    assert_eq!(urls, vec![
+        "https://doc.rust-lang.org/book/",
+        "https://doc.rust-lang.org/std/",
+        "https://this-week-in-rust.org",
+        "https://radio.example.org/stream",
+    ]);
+    assert_eq!(bookmarks[0].title, "The Rust Book");
+    assert_eq!(bookmarks[0].tags, vec!["Rust"]);
+    assert_eq!(bookmarks[3].tags, vec!["Music"]);
+}
+
+#[test]
+fn chromium_folders_become_tags() {
+    let json = std::fs::read_to_string(Path::new(FIXTURES).join("chromium/Default/Bookmarks")).unwrap();
+    let bookmarks = parse_chromium(&json);
+    assert_eq!(bookmarks.len(), 2);
+    assert_eq!(bookmarks[1].title, "Wayland protocols");
+    assert_eq!(bookmarks[1].tags, vec!["Bookmarks bar", "Wayland"]);
+    assert!(parse_chromium("not json").is_empty());
+}
+
+#[test]
+fn firefox_rows_are_parsed() {
+    let bookmarks = parse_sqlite_rows("Mozilla\thttps://mozilla.org\n\thttps://example.org\nbroken row\n");
+    assert_eq!(bookmarks.len(), 2);
+    assert_eq!(bookmarks[0].title, "Mozilla");
+    assert_eq!(bookmarks[1].url, "https://example.org");
+}
+
+#[test]
+fn bookmarks_match_title_url_and_tags() {
+    let haystack: Vec<FileEntity> = fixture("bookmarks.toml").into_iter()
+        .chain(fixture("bookmarks.md"))
+        .map(|b| FileEntity::Provider(bookmark_entity(b)))
+        .collect();
+    let mut matcher = Matcher::new(nucleo::Config::DEFAULT);
+
+    assert_eq!(titles(&bookmark_lookup(&haystack, "rust book", &mut matcher))[0], "The Rust Book");
+    assert_eq!(titles(&bookmark_lookup(&haystack, "wm", &mut matcher))[0], "Hyprland wiki");
+    // untitled bookmarks are shown by URL
+    assert_eq!(titles(&bookmark_lookup(&haystack, "docs.rs", &mut matcher))[0], "https://docs.rs");
+
+    let FileEntity::Provider(entity) = &haystack[0] else { panic!("expected a provider entity") };
+    assert!(matches!(entity.dispatcher, Dispatcher::Hyprctl));
+    assert_eq!(entity.action.as_deref(), Some("xdg-open 'https://wiki.hypr.land'"));
+}
+
+#[test]
+fn typed_urls_are_recognised() {
+    assert_eq!(as_url("https://example.org/a?b=c").as_deref(), Some("https://example.org/a?b=c"));
+    assert_eq!(as_url("github.com/hyprwm").as_deref(), Some("https://github.com/hyprwm"));
+    assert_eq!(as_url("www.example.test").as_deref(), Some("https://www.example.test"));
+    assert_eq!(as_url("localhost:8080").as_deref(), Some("http://localhost:8080"));
+    assert_eq!(as_url("127.0.0.1:3000/api").as_deref(), Some("http://127.0.0.1:3000/api"));
+    assert_eq!(as_url("[::1]:8000").as_deref(), Some("http://[::1]:8000"));
+    assert_eq!(as_url("app.localhost").as_deref(), Some("http://app.localhost"));
+    assert_eq!(as_url("192.168.1.1"), None);
+
+    assert_eq!(as_url("firefox"), None);
+    assert_eq!(as_url("install.sh"), None);
+    assert_eq!(as_url("main.rs"), None);
+    assert_eq!(as_url("open example.com"), None);
+}
+
+#[test]
+fn url_query_gets_an_open_result() {
+    let results = url_lookup("example.com");
+    assert_eq!(titles(&results), vec!["Open URL"]);
+    assert!(url_lookup("firefox").is_empty());
+}
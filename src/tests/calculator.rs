@@ -0,0 +1,91 @@
+use crate::{
+    providers::calculator::{calc_lookup, evaluate, evaluate_query, format_number, CalcError},
+    search::entity_model::{FileEntity, VirtualAction},
+};
+
+fn calc(query: &str) -> String {
+    evaluate_query(query).map(|(_, shown)| shown).unwrap_or_else(|e| format!("error: {}", e))
+}
+
+#[test]
+fn arithmetic_precedence() {
+    assert_eq!(calc("2^10 * 3"), "3072");
+    assert_eq!(calc("1 + 2 * 3"), "7");
+    assert_eq!(calc("(1 + 2) * 3"), "9");
+    assert_eq!(calc("2^3^2"), "512");
+    assert_eq!(calc("-2^2"), "-4");
+    assert_eq!(calc("2^-1"), "0.5");
+    assert_eq!(calc("10 % 4"), "2");
+    assert_eq!(calc("5!"), "120");
+    assert_eq!(calc("2 ** 8"), "256");
+    assert_eq!(calc("1 / 3"), "0.3333333333");
+}
+
+#[test]
+fn functions_and_constants() {
+    assert_eq!(calc("sqrt(16) + abs(-2)"), "6");
+    assert_eq!(calc("max(1, 7, 3)"), "7");
+    assert_eq!(calc("log(1000)"), "3");
+    assert_eq!(calc("round(pi * 100)"), "314");
+    assert_eq!(calc("2pi"), calc("2 * pi"));
+    assert_eq!(evaluate("sqrt(1, 2)"), Err(CalcError::WrongArgCount("sqrt".into())));
+    assert_eq!(evaluate("foo(1)"), Err(CalcError::UnknownName("foo".into())));
+}
+
+#[test]
+fn radix_literals_and_output() {
+    assert_eq!(calc("0xff + 0b1 + 0o10"), "264");
+    assert_eq!(calc("255 in hex"), "0xff");
+    assert_eq!(calc("0xf0 to bin"), "0b11110000");
+    assert_eq!(calc("8 in oct"), "0o10");
+    assert_eq!(calc("0x1_000 in dec"), "4096");
+}
+
+#[test]
+fn unit_conversions() {
+    assert_eq!(calc("12 km in mi"), "7.4564543068 mi");
+    assert_eq!(calc("12km to mi"), "7.4564543068 mi");
+    assert_eq!(calc("3 in in cm"), "7.62 cm");
+    assert_eq!(calc("100 c in f"), "212 °F");
+    assert_eq!(calc("0 K to celsius"), "-273.15 °C");
+    assert_eq!(calc("1 GiB in MB"), "1073.741824 MB");
+    assert_eq!(calc("90 min in h"), "1.5 h");
+    assert_eq!(calc("2 * 50 km/h in mph"), "62.1371192237 mph");
+    assert_eq!(calc("1 kg in km"), "error: can't convert kg to km");
+    assert_eq!(calc("1 kg in smoots"), "error: unknown unit 'smoots'");
+}
+
+#[test]
+fn number_formatting() {
+    assert_eq!(format_number(1e20).unwrap(), "1e20");
+    assert_eq!(format_number(-42.0).unwrap(), "-42");
+    assert_eq!(format_number(0.1 + 0.2).unwrap(), "0.3");
+    assert_eq!(format_number(f64::NAN), Err(CalcError::NotFinite));
+}
+
+#[test]
+fn lookup_copies_the_value() {
+    let results = calc_lookup("= 2^10 * 3");
+    assert_eq!(results.len(), 1);
+    let FileEntity::Virtual(v) = &results[0].file_entity else { panic!("expected a virtual result") };
+    assert_eq!(v.ui_name, "= 3072");
+    assert_eq!(v.action, VirtualAction::CopyToClipboard("3072".into()));
+
+    let results = calc_lookup("12 km in mi");
+    let FileEntity::Virtual(v) = &results[0].file_entity else { panic!("expected a virtual result") };
+    assert_eq!(v.ui_name, "12 km = 7.4564543068 mi");
+    assert_eq!(v.action, VirtualAction::CopyToClipboard("7.4564543068".into()));
+}
+
+#[test]
+fn lookup_ignores_other_queries() {
+    assert!(calc_lookup("firefox").is_empty());
+    assert!(calc_lookup("move tab to window").is_empty());
+    assert!(calc_lookup("=").is_empty());
+
+    // errors are only shown for calculator queries
+    let results = calc_lookup("= 2 +");
+    let FileEntity::Virtual(v) = &results[0].file_entity else { panic!("expected a virtual result") };
+    assert_eq!(v.ui_name, "= incomplete expression");
+    assert_eq!(v.action, VirtualAction::None);
+}
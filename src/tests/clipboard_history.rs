@@ -0,0 +1,137 @@
+use std::os::unix::fs::PermissionsExt;
+
+use nucleo::Matcher;
+
+use crate::{
+    config::config::ClipboardConfig,
+    providers::clipboard::{clip_lookup, preview, start_clipboard_history, ClipboardHistory},
+    search::entity_model::{FileEntity, VirtualAction},
+    system::clipboard::should_store,
+};
+
+const DAY: u64 = 86_400;
+
+fn limits() -> ClipboardConfig {
+    ClipboardConfig { max_entries: 3, max_age_days: 2, max_entry_bytes: 16, ..ClipboardConfig::default() }
+}
+
+fn texts(history: &ClipboardHistory) -> Vec<&str> {
+    history.entries().iter().map(|e| e.text.as_str()).collect()
+}
+
+fn restored(results: &[crate::search::entity_model::LauncherEntity]) -> Vec<String> {
+    results.iter()
+        .filter_map(|r| match &r.file_entity {
+            FileEntity::Virtual(v) => match &v.action {
+                VirtualAction::RestoreClip(text) => Some(text.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn copies_are_added_most_recent_first() {
+    let mut history = ClipboardHistory::default();
+    history.add("one".into(), 10, &limits());
+    history.add("two".into(), 20, &limits());
+    history.add("one".into(), 30, &limits());
+
+    assert_eq!(texts(&history), vec!["one", "two"]);
+    assert_eq!(history.entries()[0].copied_at, 30);
+}
+
+#[test]
+fn limits_are_applied() {
+    let mut history = ClipboardHistory::default();
+    history.add("old".into(), 0, &limits());
+    history.add("a".into(), 3 * DAY, &limits());
+    assert_eq!(texts(&history), vec!["a"], "entries older than max_age_days are dropped");
+
+    history.add("b".into(), 3 * DAY, &limits());
+    history.add("c".into(), 3 * DAY, &limits());
+    history.add("d".into(), 3 * DAY, &limits());
+    assert_eq!(texts(&history), vec!["d", "c", "b"], "only max_entries are kept");
+
+    history.add("this copy is far too large".into(), 3 * DAY, &limits());
+    assert_eq!(texts(&history), vec!["d", "c", "b"]);
+}
+
+#[test]
+fn entries_can_be_removed() {
+    let mut history = ClipboardHistory::default();
+    history.add("keep".into(), 0, &limits());
+    history.add("secret".into(), 0, &limits());
+
+    assert!(history.remove("secret"));
+    assert!(!history.remove("secret"));
+    assert_eq!(texts(&history), vec!["keep"]);
+}
+
+#[test]
+fn history_is_saved_private() {
+    let path = std::env::temp_dir()
+        .join(format!("shunpo-clipboard-{}", std::process::id()))
+        .join("clipboard_history.json");
+    let mut history = ClipboardHistory::default();
+    history.add("saved".into(), 5, &limits());
+    history.save(&path).unwrap();
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    assert_eq!(ClipboardHistory::load(&path).entries(), history.entries());
+    assert!(ClipboardHistory::load(&path.with_file_name("missing.json")).entries().is_empty());
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn search_matches_entries() {
+    let mut history = ClipboardHistory::default();
+    for text in ["cargo build --release", "https://example.com", "hello world"] {
+        history.add(text.into(), 0, &ClipboardConfig::default());
+    }
+    let mut matcher = Matcher::new(nucleo::Config::DEFAULT);
+
+    let recent = clip_lookup(&history, "", &mut matcher);
+    assert_eq!(restored(&recent), vec!["hello world", "https://example.com", "cargo build --release"]);
+
+    let matched = clip_lookup(&history, "example", &mut matcher);
+    assert_eq!(restored(&matched), vec!["https://example.com"]);
+}
+
+#[test]
+fn previews_are_single_lines() {
+    assert_eq!(preview("  short  "), "short");
+    assert_eq!(preview("first line\nsecond line"), "first line…");
+    assert_eq!(preview(&"x".repeat(100)).chars().count(), 81);
+}
+
+#[test]
+fn password_manager_copies_are_not_stored() {
+    let excluded = ClipboardConfig::default().excluded_mime_types;
+    let types = |t: &[&str]| t.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+    assert!(should_store(&types(&["text/plain;charset=utf-8", "UTF8_STRING"]), &excluded));
+    assert!(!should_store(&types(&["text/plain", "x-kde-passwordManagerHint"]), &excluded));
+    assert!(!should_store(&types(&["image/png"]), &excluded));
+    // the password manager hint is skipped even when the list doesn't name it
+    assert!(!should_store(&types(&["text/plain", "x-kde-passwordManagerHint"]), &[]));
+    assert!(!should_store(&types(&["text/plain", "application/x-nspasswordmanager"]), &excluded));
+}
+
+#[test]
+fn history_is_opt_in() {
+    assert!(!ClipboardConfig::default().enabled);
+    let config: ClipboardConfig = toml::from_str("enabled = true").unwrap();
+    assert!(config.enabled);
+    assert_eq!(config.max_entries, 200);
+}
+
+#[test]
+fn disabled_history_is_not_loaded() {
+    // a history file kept from when it was enabled stays unread
+    let history = start_clipboard_history(&ClipboardConfig::default());
+    assert!(history.lock().unwrap().entries().is_empty());
+}
@@ -0,0 +1,52 @@
+use nucleo::Matcher;
+
+use crate::{
+    config::config::EmojiAction,
+    providers::emoji::{emoji_entities, emoji_lookup, emoji_table},
+    search::entity_model::{FileEntity, LauncherEntity, VirtualAction},
+};
+
+fn first(results: &[LauncherEntity]) -> (String, VirtualAction) {
+    match &results[0].file_entity {
+        FileEntity::Virtual(v) => (v.ui_name.clone(), v.action.clone()),
+        _ => panic!("expected a virtual result"),
+    }
+}
+
+#[test]
+fn table_is_parsed() {
+    let table = emoji_table();
+    assert!(table.len() > 1000);
+    assert!(table.iter().all(|e| !e.glyph.is_empty() && !e.name.is_empty()));
+
+    let thumbs = table.iter().find(|e| e.glyph == "👍").unwrap();
+    assert_eq!(thumbs.name, "thumbs up sign");
+    assert!(thumbs.keywords.contains("like"));
+}
+
+#[test]
+fn names_and_keywords_are_searched() {
+    let haystack = emoji_entities(EmojiAction::Copy);
+    let mut matcher = Matcher::new(nucleo::Config::DEFAULT);
+
+    let (name, action) = first(&emoji_lookup(&haystack, "thumbs up", &mut matcher));
+    assert_eq!(name, "👍   thumbs up sign");
+    assert_eq!(action, VirtualAction::CopyToClipboard("👍".into()));
+
+    let (name, _) = first(&emoji_lookup(&haystack, "tada", &mut matcher));
+    assert!(name.starts_with("🎉"));
+
+    let (name, _) = first(&emoji_lookup(&haystack, "em dash", &mut matcher));
+    assert!(name.starts_with("—"));
+
+    assert!(emoji_lookup(&haystack, " ", &mut matcher).is_empty());
+}
+
+#[test]
+fn type_action_is_configurable() {
+    let haystack = emoji_entities(EmojiAction::Type);
+    let mut matcher = Matcher::new(nucleo::Config::DEFAULT);
+
+    let (_, action) = first(&emoji_lookup(&haystack, "rocket", &mut matcher));
+    assert_eq!(action, VirtualAction::TypeText("🚀".into()));
+}
@@ -0,0 +1,332 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    config::config::ShunpoConfig,
+    coordinator::types::{CoordinatorMessage, GuiMessage, MonitorWorkspacesMessage},
+    hyprland::{
+        backend::HyprctlBackend,
+        error::HyprError,
+        event_parser::HyprlandEvent,
+        events::update_state,
+        recorder::{load_session, EventRecorder, HyprctlSnapshot, RecordedEvent},
+        state::HyprlandState,
+        structs::{Client, LayerLevel, Layers, Monitor, MonitorName, Workspace},
+    },
+};
+
+//
+// harness
+//
+
+/// Fake hyprctl backend serving the most recent snapshot of a replayed session.
+struct SnapshotBackend {
+    snapshot: Mutex<HyprctlSnapshot>,
+}
+impl SnapshotBackend {
+    fn new(snapshot: HyprctlSnapshot) -> Self {
+        SnapshotBackend { snapshot: Mutex::new(snapshot) }
+    }
+    fn set(&self, snapshot: HyprctlSnapshot) {
+        *self.snapshot.lock().unwrap() = snapshot;
+    }
+}
+impl HyprctlBackend for SnapshotBackend {
+    fn monitors(&self) -> Result<Vec<Monitor>, HyprError> {
+        Ok(self.snapshot.lock().unwrap().monitors.clone())
+    }
+    fn workspaces(&self) -> Result<Vec<Workspace>, HyprError> {
+        Ok(self.snapshot.lock().unwrap().workspaces.clone())
+    }
+    fn layers(&self) -> Result<Layers, HyprError> {
+        Ok(self.snapshot.lock().unwrap().layers.clone())
+    }
+    fn clients(&self) -> Result<Vec<Client>, HyprError> {
+        Ok(self.snapshot.lock().unwrap().clients.clone())
+    }
+}
+
+/// GuiMessages emitted by update_state, reduced to comparable values.
+/// Workspace bars are summarized by bar_summary().
+#[derive(Debug, PartialEq)]
+enum Emitted {
+    Workspaces(String),
+    MonitorLayer(MonitorName, LayerLevel),
+    Urgent(Option<String>),
+    ActiveWindow(Option<String>),
+    Submap(Option<String>),
+    KeyboardLayout(Option<String>),
+    Screencast { text: Option<String>, hide_results: bool },
+    Other,
+}
+impl From<CoordinatorMessage> for Emitted {
+    fn from(msg: CoordinatorMessage) -> Self {
+        match msg {
+            CoordinatorMessage::HyprlandEvent(data) => match data.gui_msg {
+                GuiMessage::UpdateWorkspace(ws) => Emitted::Workspaces(bar_summary(&ws)),
+                GuiMessage::WaylandMonitorLayer { target_monitor, target_layer } => {
+                    Emitted::MonitorLayer(target_monitor, target_layer)
+                },
+                GuiMessage::UpdateUrgent(text) => Emitted::Urgent(text),
+                GuiMessage::UpdateActiveWindow(text) => Emitted::ActiveWindow(text),
+                GuiMessage::UpdateSubmap(text) => Emitted::Submap(text),
+                GuiMessage::UpdateKeyboardLayout(text) => Emitted::KeyboardLayout(text),
+                GuiMessage::UpdateScreencast { text, hide_results } => {
+                    Emitted::Screencast { text, hide_results }
+                },
+                _ => Emitted::Other,
+            },
+            _ => Emitted::Other,
+        }
+    }
+}
+
+fn test_config() -> ShunpoConfig {
+    toml::from_str(include_str!("fixtures/config.toml"))
+        .expect("should deserialize test config")
+}
+
+fn load_fixture(contents: &str) -> Vec<RecordedEvent> {
+    load_session(contents).unwrap_or_else(|e| panic!("should load session: {}", e))
+}
+
+/// Feed a recorded session through update_state, returning everything it emitted.
+fn replay(session: Vec<RecordedEvent>) -> Vec<Emitted> {
+    replay_with_config(session, test_config())
+}
+fn replay_with_config(session: Vec<RecordedEvent>, config: ShunpoConfig) -> Vec<Emitted> {
+    let initial = session.first()
+        .and_then(|r| r.snapshot.clone())
+        .expect("first recorded event should carry a snapshot");
+
+    let backend = Arc::new(SnapshotBackend::new(initial));
+    let mut state = HyprlandState::with_backend(backend.clone(), Some(config));
+
+    let mut emitted = Vec::new();
+    for record in session {
+        if let Some(snapshot) = record.snapshot {
+            backend.set(snapshot);
+        }
+        let Ok(event) = HyprlandEvent::parse_event(&record.event) else {
+            continue;
+        };
+        emitted.extend(update_state(&mut state, event).into_iter().map(Emitted::from));
+    }
+    emitted
+}
+
+/// Compact workspace bar notation: monitors separated by `|`, each workspace label suffixed with
+/// `*` focused, `+` active, `.` empty, `!` urgent, and prefixed with `~` when special.
+fn bar_summary(monitors: &[MonitorWorkspacesMessage]) -> String {
+    monitors
+        .iter()
+        .map(|m| {
+            let workspaces = m.workspaces
+                .iter()
+                .map(|w| {
+                    let mut s = String::new();
+                    if w.special { s.push('~'); }
+                    s.push_str(&w.label);
+                    if w.focused { s.push('*'); }
+                    else if w.active { s.push('+'); }
+                    if !w.occupied { s.push('.'); }
+                    if w.urgent { s.push('!'); }
+                    s
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+            format!("{}: {}", m.monitor, workspaces)
+        })
+        .collect::<Vec<String>>()
+        .join(" | ")
+}
+
+fn bar(summary: &str) -> Emitted {
+    Emitted::Workspaces(summary.to_string())
+}
+
+//
+// sessions
+//
+
+#[test]
+fn replay_fullscreen_retargets_shunpo() {
+    let session = load_fixture(include_str!("fixtures/sessions/fullscreen_retarget.jsonl"));
+
+    assert_eq!(replay(session), vec![
+        // openlayer>>shunpo
+        bar("DP-3: WorkspaceId=1 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        // focusedmonv2>>DP-3,2
+        bar("DP-3: WorkspaceId=1 2* 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5+ 6"),
+        // fullscreen>>0: DP-3 is free and has priority
+        Emitted::MonitorLayer(MonitorName::from("DP-3"), LayerLevel::Overlay),
+        // workspacev2>>6,6: shunpo already on target, no retarget
+        bar("DP-3: WorkspaceId=1 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5 6*"),
+        // fullscreen>>1: DP-3 is fullscreen again
+        Emitted::MonitorLayer(MonitorName::from("DP-2"), LayerLevel::Overlay),
+    ]);
+}
+
+#[test]
+fn replay_monitor_hotplug_updates_workspaces() {
+    let session = load_fixture(include_str!("fixtures/sessions/monitor_hotplug.jsonl"));
+
+    assert_eq!(replay(session), vec![
+        // openlayer>>shunpo
+        bar("DP-2: WorkspaceId=5* 6"),
+        // monitoraddedv2>>1,DP-3,Monitor 1 description
+        bar("DP-3: WorkspaceId=1 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+    ]);
+}
+
+#[test]
+fn replay_workspace_occupancy_and_names() {
+    let session = load_fixture(include_str!("fixtures/sessions/workspace_occupancy.jsonl"));
+
+    assert_eq!(replay(session), vec![
+        // openlayer>>shunpo
+        bar("DP-3: WorkspaceId=1 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        // closewindow: last window on workspace 1 closed
+        bar("DP-3: WorkspaceId=1. 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        // renameworkspace>>3,code
+        bar("DP-3: WorkspaceId=1. 2+ code WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        // activespecialv2: magic shown on DP-3
+        bar("DP-3: WorkspaceId=1. 2+ code WorkspaceId=4 ~magic+ | DP-2: WorkspaceId=5* 6"),
+    ]);
+}
+
+#[test]
+fn replay_urgent_window_until_focused() {
+    let session = load_fixture(include_str!("fixtures/sessions/urgent_window.jsonl"));
+
+    assert_eq!(replay(session), vec![
+        // openlayer>>shunpo
+        bar("DP-3: WorkspaceId=1 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        // urgent>>0123456789a6: window on workspace 1
+        bar("DP-3: WorkspaceId=1! 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        Emitted::Urgent(Some("test.client.class6".to_string())),
+        // movewindowv2: urgent window follows to workspace 3
+        bar("DP-3: WorkspaceId=1. 2+ 3! WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        // activewindowv2>>0123456789a6: focused, cleared
+        bar("DP-3: WorkspaceId=1. 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        Emitted::Urgent(None),
+    ]);
+}
+
+#[test]
+fn replay_active_window_segment() {
+    let session = load_fixture(include_str!("fixtures/sessions/active_window.jsonl"));
+    let mut config = test_config();
+    config.bar.active_window = true;
+    config.bar.active_window_format = String::from("{class} | {title}");
+    config.bar.active_window_max_len = 40;
+
+    let active = |text: &str| Emitted::ActiveWindow(Some(text.to_string()));
+    assert_eq!(replay_with_config(session, config), vec![
+        // openlayer>>shunpo: seeded from the client with focusHistoryID 0
+        bar("DP-3: WorkspaceId=1 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        active("test.client.class10 | Test Client Title…"),
+        // activewindow>>test.client.class6,Test Client Title 6
+        active("test.client.class6 | Test Client Title 6"),
+        // windowtitlev2 for another window is ignored, the active one is truncated
+        active("test.client.class6 | nvim: notes, todo.…"),
+        // activewindow>>, : nothing focused
+        Emitted::ActiveWindow(None),
+    ]);
+}
+
+#[test]
+fn replay_submap_and_keyboard_layout() {
+    let session = load_fixture(include_str!("fixtures/sessions/submap_layout.jsonl"));
+    let mut config = test_config();
+    config.bar.keyboard_layout = true;
+    config.bar.keyboard_name = Some(String::from("at-translated-set-2-keyboard"));
+    config.bar.keyboard_layouts.insert(String::from("German"), String::from("de"));
+
+    assert_eq!(replay_with_config(session, config), vec![
+        // openlayer>>shunpo
+        bar("DP-3: WorkspaceId=1 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        // submap>>resize, then back to the default submap
+        Emitted::Submap(Some("resize".to_string())),
+        Emitted::Submap(None),
+        // activelayout: virtual keyboard is ignored, configured and fallback short names
+        Emitted::KeyboardLayout(Some("de".to_string())),
+        Emitted::KeyboardLayout(Some("EN".to_string())),
+    ]);
+}
+
+#[test]
+fn replay_screencast_indicator() {
+    let session = load_fixture(include_str!("fixtures/sessions/screencast.jsonl"));
+    let mut config = test_config();
+    config.screencast_hide_results = true;
+
+    let sharing = |text: &str| Emitted::Screencast {
+        text: Some(text.to_string()),
+        hide_results: true,
+    };
+    assert_eq!(replay_with_config(session, config), vec![
+        // openlayer>>shunpo
+        bar("DP-3: WorkspaceId=1 2+ 3 WorkspaceId=4 ~magic | DP-2: WorkspaceId=5* 6"),
+        // screencast>>1,0: monitor shared
+        sharing("Sharing monitor"),
+        // screencast>>0,0: stopped
+        Emitted::Screencast { text: None, hide_results: false },
+        // screencast>>1,1: window shared
+        sharing("Sharing window"),
+    ]);
+}
+
+#[test]
+fn snapshot_from_hyprctl_fixtures_locates_shunpo() {
+    let snapshot = HyprctlSnapshot {
+        monitors: serde_json::from_str(include_str!("fixtures/hyprctl_monitors.json")).unwrap(),
+        workspaces: serde_json::from_str(include_str!("fixtures/hyprctl_workspaces.json")).unwrap(),
+        layers: serde_json::from_str(include_str!("fixtures/hyprctl_layers.json")).unwrap(),
+        clients: serde_json::from_str(include_str!("fixtures/hyprctl_clients.json")).unwrap(),
+    };
+    let backend = Arc::new(SnapshotBackend::new(snapshot));
+    let state = HyprlandState::populate(Some(test_config()), backend)
+        .unwrap_or_else(|e| panic!("should populate state: {}", e));
+
+    // the shunpo layer sits on level 3 of DP-2
+    let (monitor, layer) = state.get_shunpo_monitor_layer()
+        .unwrap_or_else(|e| panic!("should find shunpo layer: {}", e));
+    assert_eq!(monitor, MonitorName::from("DP-2"));
+    assert_eq!(layer, LayerLevel::Overlay);
+}
+
+//
+// recorder
+//
+
+#[test]
+fn recorder_stores_snapshots_only_on_change() {
+    let session = load_fixture(include_str!("fixtures/sessions/fullscreen_retarget.jsonl"));
+    let first = session[0].snapshot.clone().expect("first event should carry a snapshot");
+    let second = session[2].snapshot.clone().expect("third event should carry a snapshot");
+
+    let path = std::env::temp_dir()
+        .join(format!("shunpo-recorder-{}.jsonl", std::process::id()));
+    let backend = SnapshotBackend::new(first);
+    let mut recorder = EventRecorder::create(&path)
+        .unwrap_or_else(|e| panic!("should create recorder: {}", e));
+
+    let record = |recorder: &mut EventRecorder, line: &str| {
+        recorder.record(line, &backend)
+            .unwrap_or_else(|e| panic!("should record event: {}", e));
+    };
+    record(&mut recorder, "openlayer>>shunpo");
+    record(&mut recorder, "focusedmonv2>>DP-3,2");
+    backend.set(second);
+    record(&mut recorder, "fullscreen>>0");
+
+    let contents = std::fs::read_to_string(&path).expect("should read recorded session");
+    let _ = std::fs::remove_file(&path);
+    let recorded = load_fixture(&contents);
+
+    assert_eq!(recorded.len(), 3);
+    assert_eq!(recorded[1].event, "focusedmonv2>>DP-3,2");
+    assert!(recorded[0].snapshot.is_some());
+    assert!(recorded[1].snapshot.is_none(), "unchanged snapshot should not be stored");
+    assert!(recorded[2].snapshot.is_some());
+}
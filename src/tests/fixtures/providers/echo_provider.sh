@@ -0,0 +1,6 @@
+#!/bin/sh
+# Stand-in provider: echoes the query back as results
+printf '{"title": "You typed: %s", "subtitle": "echo provider", "icon": "edit-find", "action": "echo %s"}\n' "$1" "$1"
+echo 'not json'
+echo ''
+echo '{"title": "No action"}'
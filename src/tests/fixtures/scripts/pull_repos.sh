@@ -0,0 +1,13 @@
+#!/bin/sh
+# shunpo-name: Pull all repos
+# shunpo-description: git pull in every repository under ~/src
+# shunpo-keywords: git, sync
+# shunpo-mode: background
+# shunpo-arg: branch
+# shunpo-arg: remote = origin | upstream
+# shunpo-icon: folder-download
+# shunpo-name-typo: ignored
+
+for repo in ~/src/*/; do
+    git -C "$repo" pull "$2" "$1"
+done
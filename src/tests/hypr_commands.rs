@@ -0,0 +1,132 @@
+use crate::{
+    hyprland::{
+        commands::{HyprCommand, Layout, WorkspaceArg},
+        hyprctl::WindowTarget,
+        structs::WindowAddr,
+    },
+    search::{entity_model::{Export, FileEntity}, hypr_commands::hypr_command_lookup},
+};
+
+fn parse_ok(query: &str) -> HyprCommand {
+    match HyprCommand::parse(query) {
+        Some(Ok(cmd)) => cmd,
+        Some(Err(e)) => panic!("{} should parse: {}", query, e),
+        None => panic!("{} should be a command", query),
+    }
+}
+
+fn parse_err(query: &str) -> bool {
+    matches!(HyprCommand::parse(query), Some(Err(_)))
+}
+
+fn workspace(arg: &str) -> WorkspaceArg {
+    WorkspaceArg::parse(arg).unwrap_or_else(|e| panic!("{} should parse: {}", arg, e))
+}
+
+//
+// parsing
+//
+
+#[test]
+fn parses_commands() {
+    assert_eq!(parse_ok(":ws 3"), HyprCommand::Workspace(workspace("3")));
+    assert_eq!(parse_ok(":movetows  4 "), HyprCommand::MoveToWorkspace(workspace("4")));
+    assert_eq!(parse_ok(":float"), HyprCommand::ToggleFloating);
+    assert_eq!(parse_ok(":fullscreen"), HyprCommand::Fullscreen);
+    assert_eq!(parse_ok(":kill"), HyprCommand::Kill);
+    assert_eq!(parse_ok(":pin"), HyprCommand::Pin);
+    assert_eq!(parse_ok(":layout"), HyprCommand::Layout(None));
+    assert_eq!(parse_ok(":layout master"), HyprCommand::Layout(Some(Layout::Master)));
+}
+
+#[test]
+fn rejects_invalid_arguments() {
+    assert!(parse_err(":ws"));
+    assert!(parse_err(":ws 0"));
+    assert!(parse_err(":ws 1 2"));
+    assert!(parse_err(":movetows 3,address:0x1"));
+    assert!(parse_err(":float now"));
+    assert!(parse_err(":layout spiral"));
+}
+
+#[test]
+fn ignores_unknown_commands() {
+    assert!(HyprCommand::parse(":wall").is_none());
+    assert!(HyprCommand::parse("ws 3").is_none());
+}
+
+#[test]
+fn workspace_args() {
+    assert_eq!(workspace("+1").to_string(), "+1");
+    assert_eq!(workspace("e-1").to_string(), "e-1");
+    assert_eq!(workspace("previous").to_string(), "previous");
+    assert_eq!(workspace("special:magic").to_string(), "special:magic");
+    assert_eq!(workspace("code").to_string(), "name:code");
+}
+
+//
+// dispatch
+//
+
+#[test]
+fn window_commands_target_address() {
+    let window = WindowTarget::Address(WindowAddr::from("0123456789a6"));
+
+    assert_eq!(
+        parse_ok(":movetows 4").hyprctl_args(&window, None),
+        vec!["dispatch", "movetoworkspace", "4,address:0x0123456789a6"],
+    );
+    assert_eq!(
+        parse_ok(":kill").hyprctl_args(&window, None),
+        vec!["dispatch", "closewindow", "address:0x0123456789a6"],
+    );
+    assert_eq!(
+        WindowTarget::Address(WindowAddr::from("0x0123456789a6")).selector(),
+        "address:0x0123456789a6",
+    );
+    assert_eq!(WindowTarget::InitialTitle("shunpo".into()).selector(), "initialtitle:shunpo");
+}
+
+#[test]
+fn layout_toggles_from_current() {
+    let cmd = parse_ok(":layout");
+
+    assert_eq!(
+        cmd.hyprctl_args(&WindowTarget::Active, Some("master")),
+        vec!["keyword", "general:layout", "dwindle"],
+    );
+    assert_eq!(
+        cmd.hyprctl_args(&WindowTarget::Active, Some("dwindle")),
+        vec!["keyword", "general:layout", "master"],
+    );
+}
+
+//
+// launcher results
+//
+
+#[test]
+fn lookup_previews_valid_command() {
+    let results = hypr_command_lookup(":ws 3");
+
+    assert_eq!(results.len(), 1);
+    assert!(matches!(results[0].file_entity, FileEntity::HyprCommand(_)));
+    assert_eq!(results[0].file_entity.ui_name(), "Go to workspace 3");
+}
+
+#[test]
+fn lookup_shows_usage_and_hints() {
+    let invalid = hypr_command_lookup(":ws");
+    assert_eq!(invalid.len(), 1);
+    assert!(matches!(invalid[0].file_entity, FileEntity::Virtual(_)));
+    assert_eq!(invalid[0].file_entity.ui_name(), ":ws <workspace>: missing workspace");
+
+    let hints: Vec<String> = hypr_command_lookup(":f")
+        .iter()
+        .map(|r| r.file_entity.ui_name().clone())
+        .collect();
+    assert_eq!(hints, vec![
+        ":float - Toggle floating for active window",
+        ":fullscreen - Toggle fullscreen for active window",
+    ]);
+}
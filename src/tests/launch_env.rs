@@ -0,0 +1,72 @@
+use std::{collections::HashMap, process::Command, time::Duration};
+
+use crate::system::{launch::{shell_quote, spawn_tracked}, launch_env::LaunchEnv};
+
+fn base() -> Vec<(String, String)> {
+    vec![
+        ("PATH".into(), "/usr/bin:/bin".into()),
+        ("GSK_RENDERER".into(), "vulkan".into()),
+        ("SHUNPO_DEBUG".into(), "1".into()),
+        ("XDG_ACTIVATION_TOKEN".into(), "token".into()),
+    ]
+}
+
+fn child_env(env: &LaunchEnv) -> HashMap<String, String> {
+    let mut command = Command::new("sh");
+    env.apply(&mut command);
+    let output = command.arg("-c").arg("env").output().expect("sh runs");
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|l| l.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn internal_variables_are_stripped() {
+    let vars = LaunchEnv::new(base()).vars();
+    assert_eq!(vars.get("GSK_RENDERER").map(String::as_str), Some("vulkan"));
+    assert!(!vars.contains_key("SHUNPO_DEBUG"));
+    assert!(!vars.contains_key("XDG_ACTIVATION_TOKEN"));
+}
+
+#[test]
+fn child_gets_original_environment_with_overrides() {
+    let overrides = HashMap::from([
+        ("GDK_BACKEND".to_string(), "x11".to_string()),
+        ("PATH".to_string(), "/opt/app/bin:/usr/bin:/bin".to_string()),
+    ]);
+    let env = child_env(&LaunchEnv::new(base()).with_overrides(&overrides));
+
+    assert_eq!(env.get("GSK_RENDERER").map(String::as_str), Some("vulkan"));
+    assert_eq!(env.get("GDK_BACKEND").map(String::as_str), Some("x11"));
+    assert_eq!(env.get("PATH").map(String::as_str), Some("/opt/app/bin:/usr/bin:/bin"));
+    assert!(!env.contains_key("SHUNPO_DEBUG"));
+    assert!(!env.contains_key("XDG_ACTIVATION_TOKEN"));
+}
+
+#[test]
+fn tracked_launch_uses_launch_environment() {
+    let overrides = HashMap::from([("SHUNPO_TEST_VALUE".to_string(), "x".to_string())]);
+    // overrides are applied even when they look internal, the user asked for them
+    let env = LaunchEnv::new(base()).with_overrides(&overrides);
+    let grace = Duration::from_secs(1);
+    assert!(spawn_tracked("test \"$SHUNPO_TEST_VALUE\" = x", grace, &env).is_ok());
+    assert!(spawn_tracked("test -n \"$XDG_ACTIVATION_TOKEN\"", grace, &env).is_err());
+}
+
+#[test]
+fn hyprland_exec_is_wrapped_with_overrides() {
+    let env = LaunchEnv::new(base());
+    assert_eq!(env.wrap_exec("firefox"), "firefox");
+
+    let overrides = HashMap::from([("MOZ_ENABLE_WAYLAND".to_string(), "1".to_string())]);
+    let env = env.with_overrides(&overrides);
+    assert_eq!(env.wrap_exec("firefox"), "env MOZ_ENABLE_WAYLAND='1' firefox");
+}
+
+#[test]
+fn shell_quote_escapes_single_quotes() {
+    assert_eq!(shell_quote("plain"), "'plain'");
+    assert_eq!(shell_quote("it's"), "'it'\\''s'");
+}
@@ -0,0 +1,165 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    config::config::{AppConfig, LaunchConfig},
+    hyprland::{
+        hyprctl::{exec_with_rules, find_client_by_class},
+        structs::{Client, MonitorName, WindowAddr},
+    },
+    search::{
+        entity_model::{Dispatcher, ExecutableEntity, ExecutableSource},
+        launch_options::{launch_option_help, resolve_hold, resolve_working_dir, split_launch_options, LaunchOptions},
+    },
+};
+
+fn monitors() -> Vec<MonitorName> {
+    vec![MonitorName::from("DP-2"), MonitorName::from("HDMI-A-1")]
+}
+
+fn split(query: &str) -> (String, Vec<String>) {
+    let (query, options) = split_launch_options(query, &monitors());
+    assert!(options.unknown.is_empty(), "unexpected unknown tokens: {:?}", options.unknown);
+    (query, options.exec_rules)
+}
+
+#[test]
+fn query_without_rules_is_unchanged() {
+    assert_eq!(split("firefox"), ("firefox".into(), vec![]));
+    assert_eq!(split("b htop"), ("b htop".into(), vec![]));
+    assert_eq!(split("mail@example"), ("mail@example".into(), vec![]));
+}
+
+#[test]
+fn trailing_tokens_become_exec_rules() {
+    assert_eq!(split("firefox @3"), ("firefox".into(), vec!["workspace 3 silent".into()]));
+    assert_eq!(split("firefox @DP-2"), ("firefox".into(), vec!["monitor DP-2".into()]));
+    assert_eq!(
+        split("b htop @special:magic @float"),
+        ("b htop".into(), vec!["workspace special:magic silent".into(), "float".into()]),
+    );
+}
+
+#[test]
+fn unknown_tokens_are_not_guessed() {
+    let (query, options) = split_launch_options("firefox @floatt @3 @DP-9 @special:", &monitors());
+    assert_eq!(query, "firefox");
+    assert_eq!(options.exec_rules, vec!["workspace 3 silent".to_string()]);
+    assert_eq!(options.unknown, vec!["@floatt", "@DP-9", "@special:"]);
+
+    let help = launch_option_help(&monitors());
+    assert!(help.contains("@float"));
+    assert!(help.contains("@DP-2, @HDMI-A-1"));
+}
+
+#[test]
+fn lone_at_is_dropped_while_typing() {
+    assert_eq!(split("firefox @"), ("firefox".into(), vec![]));
+}
+
+#[test]
+fn exec_rules_prefix_command() {
+    assert_eq!(exec_with_rules("firefox", &[]), "firefox");
+    assert_eq!(
+        exec_with_rules("firefox", &["workspace 3 silent".into(), "float".into()]),
+        "[workspace 3 silent; float] firefox",
+    );
+}
+
+//
+// run or raise
+//
+
+#[test]
+fn raise_picks_most_recently_focused_client() {
+    let clients: Vec<Client> = serde_json::from_str(include_str!("fixtures/hyprctl_clients.json"))
+        .expect("should deserialize clients JSON");
+
+    let classes = vec!["missing".to_string(), "TEST.CLIENT.INITIALCLASS6".to_string()];
+    let client = find_client_by_class(&clients, &classes).expect("should match initial class");
+    assert_eq!(client.address, WindowAddr::from("0x0123456789a6"));
+
+    assert!(find_client_by_class(&clients, &["missing".to_string()]).is_none());
+}
+
+#[test]
+fn run_or_raise_is_opt_in() {
+    let firefox = app("id = \"firefox\"\nrun_or_raise = true");
+    assert!(!LaunchConfig::default().run_or_raise);
+    assert_eq!(firefox.run_or_raise, Some(true));
+}
+
+//
+// working directory and hold
+//
+
+const HOME: &str = "/home/user";
+
+fn entity(source: ExecutableSource, path: &str, working_dir: Option<&str>) -> ExecutableEntity {
+    ExecutableEntity {
+        dispatcher: Dispatcher::Shell,
+        match_name: "".into(),
+        match_rank: None,
+        path: PathBuf::from(path),
+        ui_name: "test".into(),
+        source,
+        exec: "".into(),
+        wm_class: None,
+        working_dir: working_dir.map(PathBuf::from),
+        script: None,
+    }
+}
+
+fn app(toml: &str) -> AppConfig {
+    toml::from_str(toml).expect("should deserialize app config")
+}
+
+fn cwd(entity: &ExecutableEntity, launch: &LaunchOptions, app: Option<&AppConfig>, in_terminal: bool) -> Option<PathBuf> {
+    resolve_working_dir(entity, launch, app, in_terminal, Some(Path::new(HOME)))
+}
+
+#[test]
+fn hold_and_cwd_tokens_are_launch_options() {
+    let (query, options) = split_launch_options("! backup.sh @hold @cwd:~/src @2", &[]);
+    assert_eq!(query, "! backup.sh");
+    assert!(options.hold);
+    assert_eq!(options.cwd, Some(PathBuf::from("~/src")));
+    assert_eq!(options.exec_rules, vec!["workspace 2 silent".to_string()]);
+}
+
+#[test]
+fn working_dir_precedence() {
+    let desktop = entity(ExecutableSource::DesktopFile, "/usr/share/applications/game.desktop", Some("/opt/game"));
+    let none = LaunchOptions::default();
+    let chosen = LaunchOptions { cwd: Some("~/saves".into()), ..Default::default() };
+    let configured = app("id = \"game\"\ncwd = \"/srv/game\"");
+
+    assert_eq!(cwd(&desktop, &none, None, false), Some("/opt/game".into()));
+    assert_eq!(cwd(&desktop, &none, Some(&configured), false), Some("/srv/game".into()));
+    assert_eq!(cwd(&desktop, &chosen, Some(&configured), false), Some("/home/user/saves".into()));
+}
+
+#[test]
+fn scripts_run_in_their_directory_terminals_in_home() {
+    let script = entity(ExecutableSource::ShellScript, "/home/user/scripts/backup.sh", None);
+    let binary = entity(ExecutableSource::PathBinary, "/usr/bin/htop", None);
+    let none = LaunchOptions::default();
+
+    assert_eq!(cwd(&script, &none, None, true), Some("/home/user/scripts".into()));
+    assert_eq!(cwd(&binary, &none, None, true), Some(HOME.into()));
+    assert_eq!(cwd(&binary, &none, None, false), None);
+}
+
+#[test]
+fn hold_precedence() {
+    let script = entity(ExecutableSource::ShellScript, "/home/user/scripts/backup.sh", None);
+    let binary = entity(ExecutableSource::PathBinary, "/usr/bin/htop", None);
+    let none = LaunchOptions::default();
+    let hold = LaunchOptions { hold: true, ..Default::default() };
+    let no_hold = app("id = \"backup.sh\"\nhold = false");
+
+    assert!(!resolve_hold(&script, &none, None, false));
+    assert!(resolve_hold(&script, &none, None, true));
+    assert!(!resolve_hold(&binary, &none, None, true));
+    assert!(!resolve_hold(&script, &none, Some(&no_hold), true));
+    assert!(resolve_hold(&script, &hold, Some(&no_hold), true));
+}
@@ -0,0 +1,89 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    coordinator::launch_tracker::LaunchTracker,
+    system::{error::LaunchError, launch::{command_program, missing_program, spawn_tracked}, launch_env::LaunchEnv},
+};
+
+const GRACE: Duration = Duration::from_millis(1000);
+
+#[test]
+fn failing_command_reports_status_and_stderr() {
+    match spawn_tracked("echo starting >&2; echo boom >&2; exit 3", GRACE, &LaunchEnv::original()) {
+        Err(LaunchError::Exited { code, stderr }) => {
+            assert_eq!(code, Some(3));
+            assert_eq!(stderr, "boom");
+        },
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(()) => panic!("expected failure"),
+    }
+}
+
+#[test]
+fn successful_command_is_ok() {
+    assert!(spawn_tracked("true", GRACE, &LaunchEnv::original()).is_ok());
+}
+
+#[test]
+fn long_running_command_is_ok_after_grace() {
+    let start = Instant::now();
+    assert!(spawn_tracked("sleep 5", Duration::from_millis(100), &LaunchEnv::original()).is_ok());
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn launch_failure_messages() {
+    let exited = LaunchError::Exited { code: Some(127), stderr: "sh: nope: not found".into() };
+    assert_eq!(exited.to_string(), "exited with status 127: sh: nope: not found");
+    assert_eq!(LaunchError::NoWindow.to_string(), "no window appeared");
+}
+
+fn classes(classes: &[&str]) -> Vec<String> {
+    classes.iter().map(|c| c.to_string()).collect()
+}
+
+#[test]
+fn window_resolves_launch_of_its_class() {
+    let now = Instant::now();
+    let mut tracker = LaunchTracker::default();
+    tracker.track("Firefox".into(), classes(&["firefox"]), now, Duration::from_secs(5));
+    tracker.track("htop".into(), classes(&["htop", "wezterm"]), now, Duration::from_secs(1));
+    tracker.track("Files".into(), classes(&["org.gnome.Nautilus"]), now, Duration::from_secs(5));
+
+    assert_eq!(tracker.next_deadline(), Some(now + Duration::from_secs(1)));
+    // an unrelated window doesn't resolve any launch
+    assert!(tracker.window_opened("discord").is_none());
+    assert_eq!(tracker.window_opened("org.wezfurlong.wezterm").map(|l| l.name), Some("htop".into()));
+    assert_eq!(tracker.window_opened("org.gnome.nautilus").map(|l| l.name), Some("Files".into()));
+    assert_eq!(tracker.window_opened("firefox").map(|l| l.name), Some("Firefox".into()));
+    assert!(tracker.window_opened("firefox").is_none());
+    assert_eq!(tracker.next_deadline(), None);
+}
+
+#[test]
+fn missing_programs_are_found_before_launching() {
+    let env = LaunchEnv::new([("PATH".to_string(), "/nonexistent:/bin:/usr/bin".to_string())]);
+    assert_eq!(missing_program("firefx --new-window", &env), Some("firefx".into()));
+    assert_eq!(missing_program("env GDK_BACKEND=wayland 'nosuchapp' %U", &env), Some("nosuchapp".into()));
+    assert_eq!(missing_program("/nonexistent/app", &env), Some("/nonexistent/app".into()));
+    assert_eq!(missing_program("sh -c true", &env), None);
+    // commands the shell has to interpret are left to it
+    assert_eq!(missing_program("cd /tmp && nosuchapp", &env), None);
+    assert_eq!(missing_program("exec nosuchapp", &env), None);
+    assert_eq!(command_program("FOO=1 env BAR=2 \"app\" --x=1"), Some("app"));
+    assert_eq!(LaunchError::NotFound("firefx".into()).to_string(), "command not found: firefx");
+}
+
+#[test]
+fn expired_launches_are_removed() {
+    let now = Instant::now();
+    let mut tracker = LaunchTracker::default();
+    tracker.track("slow".into(), classes(&["slow"]), now, Duration::from_secs(5));
+    tracker.track("broken".into(), classes(&["broken"]), now, Duration::from_secs(1));
+
+    assert!(tracker.expire(now).is_empty());
+
+    let expired = tracker.expire(now + Duration::from_secs(2));
+    assert_eq!(expired.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(), vec!["broken"]);
+    assert_eq!(tracker.next_deadline(), Some(now + Duration::from_secs(5)));
+}
@@ -1,2 +1,20 @@
+pub mod event_replay;
+pub mod hypr_commands;
+pub mod launch_env;
+pub mod launch_options;
+pub mod launch_tracking;
 pub mod hypr_event_parser;
 pub mod hyprctl_parser;
+pub mod systemd_launch;
+pub mod terminal_launch;
+pub mod script_meta;
+pub mod arg_prompt;
+pub mod background_job;
+pub mod providers;
+pub mod calculator;
+pub mod clipboard_history;
+pub mod emoji;
+pub mod bookmarks;
+pub mod web_search;
+pub mod ssh_hosts;
+pub mod recent_files;
@@ -0,0 +1,97 @@
+use std::{
+    sync::{atomic::{AtomicBool, Ordering}, Arc},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    config::config::ProviderConfig,
+    providers::external::{match_provider, parse_items, provider_lookup, run_provider, ProviderItem},
+    search::entity_model::{Dispatcher, FileEntity},
+};
+
+const ECHO_PROVIDER: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/fixtures/providers/echo_provider.sh");
+
+fn provider(prefix: &str, command: &str) -> ProviderConfig {
+    ProviderConfig {
+        name: "echo".to_string(),
+        prefix: prefix.to_string(),
+        command: command.to_string(),
+        timeout_ms: None,
+    }
+}
+
+#[test]
+fn query_is_matched_by_prefix() {
+    let providers = vec![provider("gh ", "true"), provider("", "true"), provider("e ", "true")];
+
+    let (matched, query) = match_provider(&providers, "e hello world").unwrap();
+    assert_eq!(matched.prefix, "e ");
+    assert_eq!(query, "hello world");
+    assert!(match_provider(&providers, "firefox").is_none());
+}
+
+#[test]
+fn malformed_lines_are_skipped() {
+    let output = "{\"title\": \"one\", \"action\": \"xdg-open a\"}\nnot json\n\n{\"subtitle\": \"no title\"}\n{\"title\": \"two\"}\n";
+    let items = parse_items("test", output);
+
+    assert_eq!(items, vec![
+        ProviderItem { title: "one".into(), subtitle: None, icon: None, action: Some("xdg-open a".into()) },
+        ProviderItem { title: "two".into(), subtitle: None, icon: None, action: None },
+    ]);
+}
+
+#[test]
+fn script_results_become_entities() {
+    let results = provider_lookup(&provider("e ", &format!("'{}'", ECHO_PROVIDER)), "it's me", &AtomicBool::new(false)).ok().unwrap();
+    assert_eq!(results.len(), 2);
+
+    let FileEntity::Provider(first) = &results[0].file_entity else { panic!("expected a provider result") };
+    assert_eq!(first.ui_name, "You typed: it's me");
+    assert_eq!(first.subtitle.as_deref(), Some("echo provider"));
+    assert_eq!(first.icon.as_deref(), Some("edit-find"));
+    assert_eq!(first.provider, "echo");
+    assert_eq!(results[0].command, "echo it's me");
+    assert!(matches!(results[0].dispatcher, Dispatcher::Hyprctl));
+
+    // results without an action are informational
+    assert!(matches!(results[1].dispatcher, Dispatcher::Virtual));
+}
+
+#[test]
+fn slow_providers_time_out() {
+    let result = run_provider("sleep 5; true", "", Duration::from_millis(100), &AtomicBool::new(false));
+    assert_eq!(result.err().map(|e| e.to_string()).as_deref(), Some("timed out"));
+}
+
+#[test]
+fn failing_providers_report_status() {
+    let result = run_provider("exit 2;", "", Duration::from_secs(5), &AtomicBool::new(false));
+    assert_eq!(result.err().map(|e| e.to_string()).as_deref(), Some("exited with status 2"));
+}
+
+#[test]
+fn lingering_child_processes_dont_hold_results() {
+    let started = Instant::now();
+    let result = run_provider("sleep 30 & echo", "{\"title\": \"done\"}", Duration::from_secs(5), &AtomicBool::new(false));
+    assert_eq!(result.ok().as_deref(), Some("{\"title\": \"done\"}\n"));
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn cancelled_providers_are_stopped() {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let canceller = {
+        let cancel = cancel.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            cancel.store(true, Ordering::Relaxed);
+        })
+    };
+    let started = Instant::now();
+    let result = run_provider("sleep 30; true", "", Duration::from_secs(10), &cancel);
+    canceller.join().unwrap();
+
+    assert_eq!(result.err().map(|e| e.to_string()).as_deref(), Some("cancelled"));
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
@@ -0,0 +1,99 @@
+use std::{fs, path::PathBuf};
+
+use nucleo::Matcher;
+
+use crate::{
+    providers::recent_files::{expand_field_codes, file_uri_path, load_recent_files, parse_xbel, recent_entity, recent_lookup, RecentFile},
+    search::entity_model::{Export, FileEntity},
+};
+
+fn fixture() -> Vec<RecentFile> {
+    parse_xbel(include_str!("fixtures/recent_files/recently-used.xbel"))
+}
+
+#[test]
+fn local_bookmarks_are_parsed() {
+    let files = fixture();
+    // remote URIs are skipped
+    assert_eq!(files.len(), 3);
+
+    let report = &files[0];
+    assert_eq!(report.path, PathBuf::from("/home/user/Documents/Quarterly Report.pdf"));
+    assert_eq!(report.mime_type.as_deref(), Some("application/pdf"));
+    assert_eq!(report.used_at.to_rfc3339(), "2026-03-04T16:40:12+00:00");
+    // the application which used it last
+    let app = report.app.as_ref().unwrap();
+    assert_eq!(app.name, "Document Viewer");
+    assert_eq!(app.exec, "evince %u");
+
+    assert_eq!(files[1].file_name(), "café & bar.png");
+    assert!(files[2].app.is_none());
+}
+
+#[test]
+fn files_open_with_the_recorded_app() {
+    let files = fixture();
+    assert_eq!(files[0].command(), "evince 'file:///home/user/Documents/Quarterly%20Report.pdf'");
+    assert_eq!(files[1].command(), "loupe '/home/user/Pictures/café & bar.png'");
+    assert_eq!(files[2].command(), "xdg-open '/home/user/notes.md'");
+
+    let entity = recent_entity(&files[0]);
+    assert_eq!(entity.icon.as_deref(), Some("application-pdf"));
+    assert!(entity.subtitle.unwrap().contains("Document Viewer"));
+}
+
+#[test]
+fn field_codes_are_expanded_once() {
+    // the emoji is percent-encoded as `%F0%9F%8E%89`, which must not be read as field codes
+    let xbel = "<xbel><bookmark href=\"file:///tmp/%F0%9F%8E%89%20party.txt\" added=\"2026-03-01T00:00:00Z\">\
+        <bookmark:application name=\"Editor\" exec=\"&apos;gedit %u&apos;\" modified=\"2026-03-01T00:00:00Z\"/>\
+        </bookmark></xbel>";
+    let files = parse_xbel(xbel);
+    assert_eq!(files[0].path, PathBuf::from("/tmp/🎉 party.txt"));
+    assert_eq!(files[0].command(), "gedit 'file:///tmp/%F0%9F%8E%89%20party.txt'");
+
+    let path = "/tmp/🎉 100%f.txt";
+    assert_eq!(expand_field_codes("app %F", "", path), "app '/tmp/🎉 100%f.txt'");
+    assert_eq!(expand_field_codes("app --icon %i --name=%c %k %f", "", path), "app --icon  --name=  '/tmp/🎉 100%f.txt'");
+    assert_eq!(expand_field_codes("printf 100%% %f", "", "/x"), "printf 100% '/x'");
+    assert_eq!(expand_field_codes("app %i", "", path), "app '/tmp/🎉 100%f.txt'");
+}
+
+#[test]
+fn file_uris_are_decoded() {
+    assert_eq!(file_uri_path("file:///tmp/a%20b%2Fc"), Some(PathBuf::from("/tmp/a b/c")));
+    assert_eq!(file_uri_path("file://localhost/tmp/x"), Some(PathBuf::from("/tmp/x")));
+    assert_eq!(file_uri_path("file:///tmp/100%"), Some(PathBuf::from("/tmp/100%")));
+    assert_eq!(file_uri_path("file://server/share/x"), None);
+    assert_eq!(file_uri_path("sftp://host/x"), None);
+}
+
+#[test]
+fn existing_files_are_listed_by_recency() {
+    let dir = std::env::temp_dir().join(format!("shunpo-recent-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let bookmark = |name: &str, time: &str| {
+        fs::write(dir.join(name), "").unwrap();
+        format!("<bookmark href=\"file://{}/{}\" added=\"{}\" modified=\"{}\" visited=\"{}\"></bookmark>", dir.display(), name, time, time, time)
+    };
+    let xbel = format!(
+        "<xbel version=\"1.0\">{}{}<bookmark href=\"file:///nonexistent/gone.txt\" added=\"2026-02-01T00:00:00Z\"></bookmark>{}</xbel>",
+        bookmark("old.txt", "2026-01-01T00:00:00Z"),
+        bookmark("new.txt", "2026-03-01T00:00:00Z"),
+        bookmark("mid.txt", "2026-02-01T00:00:00Z"),
+    );
+    fs::write(dir.join("recently-used.xbel"), xbel).unwrap();
+
+    let files = load_recent_files(&dir.join("recently-used.xbel"));
+    let haystack: Vec<FileEntity> = files.iter().map(|f| FileEntity::Provider(recent_entity(f))).collect();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let mut matcher = Matcher::new(nucleo::Config::DEFAULT);
+    let names: Vec<String> = recent_lookup(&haystack, "", &mut matcher).iter()
+        .map(|r| r.file_entity.ui_name().clone())
+        .collect();
+    assert_eq!(names, vec!["new.txt", "mid.txt", "old.txt"]);
+
+    let results = recent_lookup(&haystack, "mid", &mut matcher);
+    assert_eq!(results[0].file_entity.ui_name(), "mid.txt");
+}
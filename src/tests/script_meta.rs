@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use crate::search::{
+    entity_loader::script_entity,
+    entity_model::{Dispatcher, LauncherEntity},
+    script_meta::{parse_script_meta, ScriptArg, ScriptMode},
+};
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/tests/fixtures/scripts").join(name)
+}
+
+#[test]
+fn header_is_parsed() {
+    let meta = parse_script_meta(include_str!("fixtures/scripts/pull_repos.sh"))
+        .expect("should find header");
+
+    assert_eq!(meta.name.as_deref(), Some("Pull all repos"));
+    assert_eq!(meta.description.as_deref(), Some("git pull in every repository under ~/src"));
+    assert_eq!(meta.keywords, vec!["git".to_string(), "sync".to_string()]);
+    assert_eq!(meta.mode, ScriptMode::Background);
+    assert_eq!(meta.icon.as_deref(), Some("folder-download"));
+    assert_eq!(meta.args, vec![
+        ScriptArg { name: "branch".into(), choices: vec![] },
+        ScriptArg { name: "remote".into(), choices: vec!["origin".into(), "upstream".into()] },
+    ]);
+}
+
+#[test]
+fn header_ends_at_first_code_line() {
+    let script = "#!/usr/bin/env python3\n# shunpo-name: Weather\n\nimport sys\n# shunpo-mode: background\n";
+    let meta = parse_script_meta(script).expect("should find header");
+    assert_eq!(meta.name.as_deref(), Some("Weather"));
+    assert_eq!(meta.mode, ScriptMode::Terminal);
+}
+
+#[test]
+fn other_comment_styles() {
+    let meta = parse_script_meta("#!/usr/bin/env node\n// shunpo-name: Node thing\n").unwrap();
+    assert_eq!(meta.name.as_deref(), Some("Node thing"));
+    let meta = parse_script_meta("#!/usr/bin/env lua\n-- shunpo-keywords: a,, b\n").unwrap();
+    assert_eq!(meta.keywords, vec!["a".to_string(), "b".to_string()]);
+}
+
+#[test]
+fn scripts_without_header() {
+    assert_eq!(parse_script_meta("#!/bin/sh\n# just a comment\necho hi\n"), None);
+    assert_eq!(parse_script_meta(""), None);
+}
+
+#[test]
+fn script_entity_uses_header() {
+    let path = fixture("pull_repos.sh");
+    let entity = script_entity(path.clone(), "pull_repos.sh".into());
+
+    assert_eq!(entity.ui_name, "Pull all repos");
+    assert_eq!(entity.match_name.to_string(), "Pull all repos git sync");
+    assert!(matches!(entity.dispatcher, Dispatcher::Background));
+    assert_eq!(
+        LauncherEntity::from_executable(&entity).command,
+        format!("'{}'", path.to_string_lossy()),
+    );
+}
@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use nucleo::Matcher;
+
+use crate::{
+    providers::ssh::{parse_known_hosts, read_ssh_config, ssh_entity, ssh_lookup, wildcard_match, SshHost},
+    search::entity_model::{Dispatcher, Export, FileEntity},
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/fixtures/ssh");
+
+fn config_hosts() -> Vec<SshHost> {
+    let dir = PathBuf::from(FIXTURES);
+    read_ssh_config(&dir.join("config"), &dir, Path::new("/nonexistent"))
+}
+
+fn names(hosts: &[SshHost]) -> Vec<&str> {
+    hosts.iter().map(|h| h.name.as_str()).collect()
+}
+
+#[test]
+fn config_hosts_and_includes_are_read() {
+    let hosts = config_hosts();
+    // patterns are skipped, includes are read where they appear
+    assert_eq!(names(&hosts), vec!["nas", "bastion", "web-1", "web-2"]);
+
+    assert_eq!(hosts[0].target(), "admin@192.168.1.20");
+    assert_eq!(hosts[1].target(), "bastion.example.com:2222");
+    // the first value wins, and `Match` blocks don't apply to earlier hosts
+    assert_eq!(hosts[3].target(), "deploy@web.internal");
+}
+
+#[test]
+fn known_hosts_skip_hashed_and_marker_lines() {
+    let contents = std::fs::read_to_string(Path::new(FIXTURES).join("known_hosts")).unwrap();
+    let hosts = parse_known_hosts(&contents);
+    assert_eq!(names(&hosts), vec!["nas", "192.168.1.20", "git.example.com", "old.example.org"]);
+    assert_eq!(hosts[2].port, Some(2200));
+}
+
+#[test]
+fn host_commands() {
+    let hosts = config_hosts();
+    // ssh reads the port of config hosts itself
+    assert_eq!(hosts[1].command(), "ssh 'bastion'");
+
+    let known = parse_known_hosts("[git.example.com]:2200 ssh-ed25519 AAAA");
+    assert_eq!(known[0].command(), "ssh -p 2200 'git.example.com'");
+
+    let entity = ssh_entity(&hosts[0]);
+    assert!(matches!(entity.dispatcher, Dispatcher::Shell));
+    assert_eq!(entity.action.as_deref(), Some("ssh 'nas'"));
+}
+
+#[test]
+fn hosts_are_fuzzy_matched() {
+    let haystack: Vec<FileEntity> = config_hosts().iter().map(|h| FileEntity::Provider(ssh_entity(h))).collect();
+    let mut matcher = Matcher::new(nucleo::Config::DEFAULT);
+
+    let results = ssh_lookup(&haystack, "bastn", &mut matcher);
+    assert_eq!(results[0].file_entity.ui_name(), "bastion");
+    // host names are matched too
+    let results = ssh_lookup(&haystack, "192.168", &mut matcher);
+    assert_eq!(results[0].file_entity.ui_name(), "nas");
+
+    assert_eq!(ssh_lookup(&haystack, "", &mut matcher).len(), 4);
+}
+
+#[test]
+fn include_wildcards() {
+    assert!(wildcard_match("*.conf", "work.conf"));
+    assert!(wildcard_match("w?rk*", "work.conf"));
+    assert!(wildcard_match("*a*b", "xaxxab"));
+    assert!(!wildcard_match("*.conf", "notes.txt"));
+    assert!(!wildcard_match("a?", "a"));
+}
@@ -0,0 +1,69 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{
+    hyprland::hyprctl::{compose_exec, ExecOptions},
+    system::{
+        launch_env::LaunchEnv,
+        systemd::{app_scope_unit, escape_unit_part, in_scope, unit_suffix},
+    },
+};
+
+#[test]
+fn unit_parts_are_escaped() {
+    assert_eq!(escape_unit_part("org.mozilla.firefox"), "org.mozilla.firefox");
+    assert_eq!(escape_unit_part("google-chrome"), "google\\x2dchrome");
+    assert_eq!(escape_unit_part("my app"), "my\\x20app");
+    assert_eq!(escape_unit_part(".hidden"), "\\x2ehidden");
+    assert_eq!(escape_unit_part("ü"), "\\xc3\\xbc");
+}
+
+#[test]
+fn scope_unit_follows_app_naming() {
+    assert_eq!(app_scope_unit("firefox", 0xbeef), "app-shunpo-firefox-beef.scope");
+    assert_eq!(
+        app_scope_unit("org.gnome.Nautilus", 1),
+        "app-shunpo-org.gnome.Nautilus-1.scope",
+    );
+    assert_eq!(app_scope_unit("google-chrome", 2), "app-shunpo-google\\x2dchrome-2.scope");
+}
+
+#[test]
+fn scope_units_are_unique_per_launch() {
+    assert_ne!(unit_suffix(), unit_suffix());
+    // the launcher pid sits in the upper half
+    assert_eq!(unit_suffix() >> 32, u64::from(std::process::id()));
+}
+
+#[test]
+fn command_runs_in_scope() {
+    assert_eq!(
+        in_scope("firefox --new-window", "app-shunpo-firefox-1.scope"),
+        "systemd-run --user --scope --quiet --collect --unit='app-shunpo-firefox-1.scope' -- sh -c 'firefox --new-window'",
+    );
+    assert_eq!(
+        in_scope("echo 'hi'", "app-shunpo-google\\x2dchrome-1.scope"),
+        "systemd-run --user --scope --quiet --collect --unit='app-shunpo-google\\x2dchrome-1.scope' -- sh -c 'echo '\\''hi'\\'''",
+    );
+}
+
+#[test]
+fn exec_rules_and_env_wrap_the_scope() {
+    let env = LaunchEnv::new(Vec::new())
+        .with_overrides(&HashMap::from([("GDK_BACKEND".to_string(), "x11".to_string())]));
+    let rules = vec!["workspace 2 silent".to_string()];
+
+    let options = ExecOptions {
+        rules: &rules,
+        env: &env,
+        scope: Some("app-shunpo-gimp-1.scope"),
+        cwd: Some(Path::new("/srv/art")),
+    };
+    assert_eq!(
+        compose_exec("gimp", &options),
+        "[workspace 2 silent] cd '/srv/art' && env GDK_BACKEND='x11' systemd-run --user --scope --quiet --collect --unit='app-shunpo-gimp-1.scope' -- sh -c 'gimp'",
+    );
+
+    let env = LaunchEnv::new(Vec::new());
+    let options = ExecOptions { rules: &[], env: &env, scope: None, cwd: None };
+    assert_eq!(compose_exec("gimp", &options), "gimp");
+}
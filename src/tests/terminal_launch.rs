@@ -0,0 +1,82 @@
+use std::{fs, os::unix::fs::PermissionsExt, path::PathBuf};
+
+use crate::system::terminal::{compose_terminal, detect_terminal, find_in_path, template_for, TerminalCommand};
+
+fn full() -> TerminalCommand {
+    TerminalCommand {
+        cmd: "htop".into(),
+        cwd: Some(PathBuf::from("/home/user/my dir")),
+        title: Some("htop".into()),
+        class: Some("shunpo.htop".into()),
+        hold: true,
+    }
+}
+
+#[test]
+fn terminals_are_matched_by_file_name() {
+    assert_eq!(template_for("/usr/bin/foot").name, "foot");
+    assert_eq!(template_for("kitty").name, "kitty");
+    assert_eq!(template_for("/opt/wezterm/bin/wezterm --config-file x").name, "wezterm");
+    assert_eq!(template_for("/usr/bin/st").name, "generic");
+}
+
+#[test]
+fn command_with_quotes_is_quoted_once() {
+    assert_eq!(
+        compose_terminal("/usr/bin/ghostty", &TerminalCommand::new("echo 'hi there'")),
+        "/usr/bin/ghostty -e sh -c 'echo '\\''hi there'\\'''",
+    );
+}
+
+#[test]
+fn terminal_templates() {
+    assert_eq!(
+        compose_terminal("ghostty", &full()),
+        "ghostty --working-directory='/home/user/my dir' --title='htop' --class='shunpo.htop' --wait-after-command=true -e sh -c 'htop'",
+    );
+    assert_eq!(
+        compose_terminal("kitty", &full()),
+        "kitty --directory '/home/user/my dir' --title 'htop' --class 'shunpo.htop' --hold sh -c 'htop'",
+    );
+    assert_eq!(
+        compose_terminal("foot", &full()),
+        "foot --working-directory='/home/user/my dir' --title='htop' --app-id='shunpo.htop' --hold sh -c 'htop'",
+    );
+}
+
+#[test]
+fn missing_flags_are_emulated_in_the_shell() {
+    // wezterm has no title or hold flag, xterm no working directory flag
+    assert_eq!(
+        compose_terminal("wezterm", &full()),
+        "wezterm start --cwd '/home/user/my dir' --class 'shunpo.htop' -- sh -c 'printf '\\''\\033]2;%s\\007'\\'' '\\''htop'\\''; htop; printf '\\''\\n[exited, press enter to close]'\\''; read -r _'",
+    );
+    // konsole has no title or class flag, the class is dropped
+    assert_eq!(
+        compose_terminal("konsole", &full()),
+        "konsole --workdir '/home/user/my dir' --hold -e sh -c 'printf '\\''\\033]2;%s\\007'\\'' '\\''htop'\\''; htop'",
+    );
+    assert_eq!(
+        compose_terminal("xterm", &TerminalCommand { hold: false, ..full() }),
+        "xterm -T 'htop' -class 'shunpo.htop' -e sh -c 'cd '\\''/home/user/my dir'\\'' && htop'",
+    );
+}
+
+#[test]
+fn terminals_are_detected_in_path() {
+    let dir = std::env::temp_dir().join(format!("shunpo-terminal-test-{}", std::process::id()));
+    let bin = dir.join("bin");
+    fs::create_dir_all(&bin).unwrap();
+
+    // not executable, skipped
+    fs::write(bin.join("kitty"), "").unwrap();
+    fs::write(bin.join("foot"), "").unwrap();
+    fs::set_permissions(bin.join("foot"), fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path_env = format!("/nonexistent:{}", bin.to_string_lossy());
+    assert_eq!(find_in_path("kitty", &path_env), None);
+    assert_eq!(detect_terminal(&path_env), Some(bin.join("foot")));
+    assert_eq!(detect_terminal("/nonexistent"), None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,83 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+
+use crate::{
+    config::config::{SearchEngineConfig, ShunpoConfig, WebSearchConfig},
+    coordinator::types::CoordinatorMessage,
+    providers::clipboard::ClipboardHistory,
+    providers::web_search::{fallback_lookup, match_engine, percent_encode, search_url, web_search_entity, web_search_lookup},
+    search::{entity_model::Export, listener::setup_search_listener},
+};
+
+#[test]
+fn keyword_selects_the_engine() {
+    let config = WebSearchConfig::default();
+
+    let (engine, terms) = match_engine(&config, "gh rust nucleo").unwrap();
+    assert_eq!(engine.name, "GitHub");
+    assert_eq!(terms, "rust nucleo");
+    let (engine, _) = match_engine(&config, "docs tokio select").unwrap();
+    assert_eq!(engine.name, "docs.rs");
+
+    // the keyword has to be a whole word
+    assert!(match_engine(&config, "ghostty").is_none());
+    assert!(match_engine(&config, "ddg").is_none());
+}
+
+#[test]
+fn terms_are_percent_encoded() {
+    assert_eq!(percent_encode("rust nucleo"), "rust%20nucleo");
+    assert_eq!(percent_encode("a&b=c/d?e#f+g"), "a%26b%3Dc%2Fd%3Fe%23f%2Bg");
+    assert_eq!(percent_encode("~file_name-1.2"), "~file_name-1.2");
+    assert_eq!(percent_encode("café"), "caf%C3%A9");
+}
+
+#[test]
+fn template_is_filled() {
+    assert_eq!(search_url("https://duckduckgo.com/?q={query}&ia=web", "c++ tips"), "https://duckduckgo.com/?q=c%2B%2B%20tips&ia=web");
+    assert_eq!(search_url("https://en.wikipedia.org/wiki/", "Rust (language)"), "https://en.wikipedia.org/wiki/Rust%20%28language%29");
+}
+
+#[test]
+fn result_row_names_the_engine() {
+    let engine = SearchEngineConfig::new("GitHub", "gh", "https://github.com/search?q={query}");
+    let entity = web_search_entity(&engine, "rust nucleo");
+    assert_eq!(entity.ui_name, "Search GitHub for \u{201c}rust nucleo\u{201d}");
+    assert_eq!(entity.provider, "GitHub");
+    assert_eq!(entity.action.as_deref(), Some("xdg-open 'https://github.com/search?q=rust%20nucleo'"));
+
+    assert!(web_search_lookup(&engine, "  ").is_empty());
+}
+
+#[test]
+fn fallback_uses_the_configured_engine() {
+    let mut config = WebSearchConfig::default();
+    assert_eq!(fallback_lookup(&config, "weather").len(), 1);
+
+    config.fallback = Some("nope".to_string());
+    assert!(fallback_lookup(&config, "weather").is_empty());
+    config.fallback = None;
+    assert!(fallback_lookup(&config, "weather").is_empty());
+}
+
+const APPLICATIONS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/tests/fixtures/applications");
+
+#[tokio::test]
+async fn fallback_follows_matching_apps() {
+    let mut config: ShunpoConfig = toml::from_str(include_str!("fixtures/config.toml"))
+        .expect("should deserialize test config");
+    config.desktop_entries_paths = vec![APPLICATIONS.to_string()];
+    let (search_tx, search_rx) = mpsc::unbounded_channel();
+    let (coord_tx, mut coord_rx) = mpsc::unbounded_channel();
+    setup_search_listener(search_rx, coord_tx, config, Arc::new(Mutex::new(ClipboardHistory::default())));
+
+    search_tx.send("weather notes".to_string()).unwrap();
+    let Some(CoordinatorMessage::SearchMessage(data)) = coord_rx.recv().await else {
+        panic!("should answer with search results");
+    };
+    let names: Vec<String> = data.results.iter().map(|r| r.file_entity.ui_name().clone()).collect();
+
+    assert!(names.iter().any(|n| n == "Weather Notes"), "app should match: {:?}", names);
+    assert_eq!(names.last().unwrap(), "Search DuckDuckGo for \u{201c}weather notes\u{201d}");
+}
@@ -24,8 +24,10 @@ use crate::{
         },
         ui_widgets::{
             clock,
+            prompt,
             results,
             search,
+            status_segment,
             volume, workspaces,
         },
     },
@@ -86,14 +88,30 @@ pub fn build_ui(
     let clock = clock();
     let workspaces = workspaces();
     workspaces.set_visible(false);
+    let active_window = status_segment("active-window-label");
+    let urgent = status_segment("urgent-label");
+    let submap = status_segment("submap-label");
+    let keyboard_layout = status_segment("keyboard-layout-label");
+    let screencast = status_segment("screencast-label");
+    let launch_failed = status_segment("launch-failed-label");
+    let job = status_segment("job-label");
+    clock_box.append(&launch_failed);
+    clock_box.append(&job);
+    clock_box.append(&screencast);
+    clock_box.append(&active_window);
+    clock_box.append(&urgent);
+    clock_box.append(&submap);
     clock_box.append(&workspaces);
+    clock_box.append(&keyboard_layout);
     clock_box.append(&clock);
 
     let header_box = Box::new(Orientation::Horizontal, 0);
     let volume = volume();
+    let prompt = prompt();
     let search = search();
     let (results_window, results) = results();
     header_box.append(&clock_box);
+    header_box.append(&prompt);
     header_box.append(&search);
     header_box.append(&volume);
 
@@ -111,6 +129,7 @@ pub fn build_ui(
 
     let search_controller = search_controller(
         search.clone(),
+        prompt.clone(),
         results.clone(),
         results_window.clone(),
         feedback_tx.clone(),
@@ -124,8 +143,16 @@ pub fn build_ui(
     ShunpoWidgets {
         window,
         workspaces,
+        urgent,
+        active_window,
+        submap,
+        keyboard_layout,
+        screencast,
+        launch_failed,
+        job,
         clock,
         volume,
+        prompt,
         search,
         results,
         results_window,
@@ -1,6 +1,6 @@
 use gtk4::gdk::{Key, ModifierType};
 use gtk4::glib::Propagation;
-use gtk4::{Entry, EventControllerKey, EventSequenceState, GestureClick, ListBox, PropagationPhase, ScrolledWindow, prelude::*};
+use gtk4::{Entry, EventControllerKey, EventSequenceState, GestureClick, Label, ListBox, PropagationPhase, ScrolledWindow, prelude::*};
 use log::error;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -9,7 +9,7 @@ use tokio::sync::mpsc;
 use crate::coordinator::types::GuiMessage;
 use crate::{
     coordinator::types::{CoordinatorMessage, FeedbackData},
-    search::entity_model::LauncherEntity,
+    search::{arg_prompt::{ArgPrompt, PromptStep}, entity_model::{Export, FileEntity, LauncherEntity, VirtualAction}},
     ui_gtk4::{helpers::result_data_from_idx, types::ShunpoState},
 };
 
@@ -52,6 +52,7 @@ pub fn window_controller(
 /// Event handler for GUI text input field
 pub fn search_controller(
     search: Entry,
+    prompt: Label,
     results: ListBox,
     results_window: ScrolledWindow,
     feedback_tx: mpsc::UnboundedSender<CoordinatorMessage>,
@@ -60,11 +61,11 @@ pub fn search_controller(
     let search_controller = EventControllerKey::new();
 
     search_controller.connect_key_pressed(
-        handle_keyboard_input_pressed(search.clone(), results.clone(), results_window.clone())
+        handle_keyboard_input_pressed(search.clone(), prompt.clone(), results.clone(), results_window.clone(), feedback_tx.clone(), state_rc.clone())
     );
 
     search_controller.connect_key_released(
-        handle_keyboard_input_released(results.clone(), search.clone(), feedback_tx.clone(), state_rc.clone())
+        handle_keyboard_input_released(results.clone(), search.clone(), prompt.clone(), feedback_tx.clone(), state_rc.clone())
     );
 
     search_controller
@@ -73,10 +74,17 @@ pub fn search_controller(
 /// Called when return key is pressed
 fn send_input(
     search: &Entry,
+    prompt: &Label,
     results: &ListBox,
     feedback_tx: &mpsc::UnboundedSender<CoordinatorMessage>,
     state_rc: &Rc<RefCell<ShunpoState>>,
+    force_new: bool,
 ) {
+    if state_rc.borrow().prompt.is_some() {
+        submit_argument(search, prompt, results, feedback_tx, state_rc);
+        return;
+    }
+
     let text = search.text();
 
     if text.is_empty() {
@@ -101,27 +109,124 @@ fn send_input(
         res = result_data_from_idx(&results, &state);
     }
 
-    if let Some(data) = res {
-        let _ = feedback_tx.send(CoordinatorMessage::Feedback(FeedbackData::Run(data)));
+    if let Some(mut data) = res {
+        data.launch.force_new = force_new;
+        // scripts declaring arguments ask for them first
+        if let Some(arg_prompt) = ArgPrompt::declared(&data) {
+            begin_prompt(arg_prompt, search, prompt, state_rc);
+            return;
+        }
+        let _ = feedback_tx.send(CoordinatorMessage::Feedback(FeedbackData::Run(Box::new(data))));
     }
     else {
         error!("Failed to match listbox to state!");
     }
 }
 
+//
+// argument prompt
+//
+
+/// Switch the search field to argument entry
+fn begin_prompt(
+    arg_prompt: ArgPrompt,
+    search: &Entry,
+    prompt: &Label,
+    state_rc: &Rc<RefCell<ShunpoState>>,
+) {
+    prompt.set_text(&arg_prompt.label());
+    prompt.set_visible(true);
+    state_rc.borrow_mut().prompt = Some(arg_prompt);
+    // state must not be borrowed here: clearing the field shows the choices through connect_changed
+    search.set_text("");
+}
+
+/// Leave argument entry, keeping the search field empty
+pub fn end_prompt(search: &Entry, prompt: &Label, state_rc: &Rc<RefCell<ShunpoState>>) {
+    prompt.set_text("");
+    prompt.set_visible(false);
+    state_rc.borrow_mut().prompt = None;
+    search.set_text("");
+}
+
+/// Accept the selected choice, or the typed text, for the current argument
+fn submit_argument(
+    search: &Entry,
+    prompt: &Label,
+    results: &ListBox,
+    feedback_tx: &mpsc::UnboundedSender<CoordinatorMessage>,
+    state_rc: &Rc<RefCell<ShunpoState>>,
+) {
+    let step = {
+        let mut state = state_rc.borrow_mut();
+        let choice = selected_choice(results, &state);
+        let Some(arg_prompt) = state.prompt.as_mut() else { return; };
+
+        let value = match choice {
+            Some(choice) if arg_prompt.has_choices() => choice,
+            _ => search.text().to_string(),
+        };
+        let step = arg_prompt.submit(&value);
+        if let PromptStep::Next = step {
+            prompt.set_text(&arg_prompt.label());
+        }
+        step
+    };
+
+    match step {
+        PromptStep::Next => search.set_text(""),
+        PromptStep::Done(entity) => {
+            end_prompt(search, prompt, state_rc);
+            let _ = feedback_tx.send(CoordinatorMessage::Feedback(FeedbackData::Run(entity)));
+        },
+    }
+}
+
+/// Text of the selected choice row
+fn selected_choice(results: &ListBox, state: &ShunpoState) -> Option<String> {
+    result_data_from_idx(results, state).map(|entity| entity.file_entity.ui_name().clone())
+}
+
+/// Tab: complete the selected choice, or enter arguments for the selected result
+fn hkb_prompt_tab(
+    search: &Entry,
+    prompt: &Label,
+    results: &ListBox,
+    state_rc: &Rc<RefCell<ShunpoState>>,
+) -> Propagation {
+    if state_rc.borrow().prompt.is_some() {
+        let choice = selected_choice(results, &state_rc.borrow());
+        if let Some(choice) = choice {
+            search.set_text(&choice);
+            search.set_position(-1);
+        }
+        return Propagation::Stop;
+    }
+
+    let selected = result_data_from_idx(results, &state_rc.borrow());
+    if let Some(entity) = selected {
+        let arg_prompt = ArgPrompt::declared(&entity)
+            .unwrap_or_else(|| ArgPrompt::free_form(&entity));
+        begin_prompt(arg_prompt, search, prompt, state_rc);
+    }
+    Propagation::Stop
+}
+
 /// Routing for keyboard input to controllers on key release
 fn handle_keyboard_input_released(
     results: ListBox,
     search: Entry,
+    prompt: Label,
     feedback_tx: mpsc::UnboundedSender<CoordinatorMessage>,
     state_rc: Rc<RefCell<ShunpoState>>,
 ) -> impl Fn(&EventControllerKey, Key, u32, ModifierType) {
     move |_, key, _code, modifier| {
         fn mod_ctrl(m: ModifierType) -> bool { m.contains(ModifierType::CONTROL_MASK) }
         fn mod_alt(m: ModifierType) -> bool { m.contains(ModifierType::ALT_MASK) }
+        fn mod_shift(m: ModifierType) -> bool { m.contains(ModifierType::SHIFT_MASK) }
 
         if key == Key::Return {
-            send_input(&search, &results, &feedback_tx, &state_rc);
+            send_input(&search, &prompt, &results, &feedback_tx, &state_rc, mod_shift(modifier));
         }
         else if mod_ctrl(modifier) && key == Key::a {
             hkb_caret_to_beginning(&search);
@@ -138,13 +243,25 @@ fn handle_keyboard_input_released(
 /// Routing for keyboard input to controllers on key press
 fn handle_keyboard_input_pressed(
     search: Entry,
+    prompt: Label,
     results: ListBox,
     results_window: ScrolledWindow,
+    feedback_tx: mpsc::UnboundedSender<CoordinatorMessage>,
+    state_rc: Rc<RefCell<ShunpoState>>,
 ) -> impl Fn(&EventControllerKey, Key, u32, ModifierType) -> Propagation {
     move |_, key, _code, modifier| {
         fn mod_ctrl(m: ModifierType) -> bool { m.contains(ModifierType::CONTROL_MASK) }
+        fn mod_shift(m: ModifierType) -> bool { m.contains(ModifierType::SHIFT_MASK) }
 
-        if mod_ctrl(modifier) && (key == Key::n || key == Key::p) {
+        if key == Key::Tab {
+            hkb_prompt_tab(&search, &prompt, &results, &state_rc)
+        }
+        // cancel argument entry instead of hiding the launcher
+        else if key == Key::Escape && state_rc.borrow().prompt.is_some() {
+            end_prompt(&search, &prompt, &state_rc);
+            Propagation::Stop
+        }
+        else if mod_ctrl(modifier) && (key == Key::n || key == Key::p) {
             hkb_nav_results(key, &results, results_window.clone())
         }
         else if mod_ctrl(modifier) && key == Key::w {
@@ -153,6 +270,12 @@ fn handle_keyboard_input_pressed(
         else if mod_ctrl(modifier) && key == Key::e {
             hkb_caret_to_end(&search)
         }
+        else if mod_ctrl(modifier) && key == Key::c {
+            hkb_cancel_job(&search, &feedback_tx)
+        }
+        else if mod_shift(modifier) && key == Key::Delete {
+            hkb_delete_clip(&results, &feedback_tx, &state_rc)
+        }
         else {
             Propagation::Proceed
         }
@@ -211,6 +334,46 @@ fn hkb_caret_to_end(search: &Entry) -> Propagation {
     Propagation::Proceed
 }
 
+/// Cancel the running background job with c^c, unless text is selected to be copied
+fn hkb_cancel_job(search: &Entry, feedback_tx: &mpsc::UnboundedSender<CoordinatorMessage>) -> Propagation {
+    if search.selection_bounds().is_some() {
+        return Propagation::Proceed;
+    }
+    let _ = feedback_tx.send(CoordinatorMessage::Feedback(FeedbackData::CancelJob));
+
+    Propagation::Stop
+}
+
+/// Remove the selected clipboard history entry with S-Delete
+fn hkb_delete_clip(
+    results: &ListBox,
+    feedback_tx: &mpsc::UnboundedSender<CoordinatorMessage>,
+    state_rc: &Rc<RefCell<ShunpoState>>,
+) -> Propagation {
+    let Some(row) = results.selected_row() else {
+        return Propagation::Proceed;
+    };
+    let idx = row.index();
+    {
+        let mut state = state_rc.borrow_mut();
+        let text = match state.results_data.get(idx as usize).map(|e| &e.file_entity) {
+            Some(FileEntity::Virtual(v)) => match &v.action {
+                VirtualAction::RestoreClip(text) => text.clone(),
+                _ => return Propagation::Proceed,
+            },
+            _ => return Propagation::Proceed,
+        };
+        let _ = feedback_tx.send(CoordinatorMessage::Feedback(FeedbackData::DeleteClip(text)));
+        state.results_data.remove(idx as usize);
+    }
+
+    results.remove(&row);
+    if let Some(next) = results.row_at_index(idx).or_else(|| results.row_at_index(idx - 1)) {
+        results.select_row(Some(&next));
+    }
+    Propagation::Stop
+}
+
 /// Move caret to beginning of input with c^a
 fn hkb_caret_to_beginning(search: &Entry) {
     search.set_position(0);
@@ -7,6 +7,7 @@ use gtk4::{
 use log::{
     error,
 };
+use nucleo::Matcher;
 use std::cell::RefCell;
 use std::rc::Rc;
 use tokio::sync::mpsc;
@@ -20,6 +21,7 @@ use crate::{
     },
     search::entity_model::{
         LauncherEntity,
+        VirtualAction,
         VirtualEntity,
     },
     ui_gtk4::{
@@ -59,8 +61,9 @@ pub fn run_shunpo(
             // ui to coordinator
             let ev_search_tx = search_tx.clone();
             let ev_feedback_ui_tx = feedback_tx.clone();
+            let ev_state = state_rc.clone();
             widgets.search.connect_changed(move |entry| {
-                handle_entry_change(entry, &ev_search_tx, &ev_feedback_ui_tx);
+                handle_entry_change(entry, &ev_search_tx, &ev_feedback_ui_tx, &ev_state);
             });
 
             // coordinator to ui
@@ -87,8 +90,16 @@ fn handle_entry_change(
     entry: &gtk4::Entry,
     search_tx: &mpsc::UnboundedSender<String>,
     feedback_tx: &mpsc::UnboundedSender<CoordinatorMessage>,
+    state_rc: &Rc<RefCell<ShunpoState>>,
 ) {
     let query = entry.text().to_string();
+    // typing leaves the job output for the results of the new query
+    if let Ok(mut state) = state_rc.try_borrow_mut() {
+        state.job_view = false;
+    }
+    if display_arg_choices(&query, feedback_tx, state_rc) {
+        return;
+    }
     if display_help_as_result(&query, &feedback_tx) {
         return;
     }
@@ -103,6 +114,29 @@ fn handle_entry_change(
     }
 }
 
+/// While entering arguments, list the matching choices instead of searching
+fn display_arg_choices(
+    query: &str,
+    feedback_tx: &mpsc::UnboundedSender<CoordinatorMessage>,
+    state_rc: &Rc<RefCell<ShunpoState>>,
+) -> bool {
+    let choices = {
+        // already borrowed while handling a GuiMessage, which clears the prompt before the field
+        let Ok(state) = state_rc.try_borrow() else {
+            return false;
+        };
+        let Some(arg_prompt) = &state.prompt else {
+            return false;
+        };
+        arg_prompt.complete(query, &mut Matcher::new(nucleo::Config::DEFAULT))
+    };
+
+    if let Err(e) = feedback_tx.send(compose_fb_help_msg(choices)) {
+        error!("Failed to send argument choices to feedback: {}", e);
+    }
+    true
+}
+
 fn display_help_as_result(query: &str, feedback_tx: &mpsc::UnboundedSender<CoordinatorMessage>) -> bool {
     let send_virtual_result = if query == ":q" {
         Some(compose_fb_help_msg(vec!["Quit shunpo".into()]))
@@ -127,10 +161,14 @@ fn compose_fb_help_msg(text: Vec<String>) -> CoordinatorMessage {
         .map(compose_launcher_entity_from_string)
         .collect();
 
+    compose_fb_results_msg(results)
+}
+
+fn compose_fb_results_msg(results: Vec<LauncherEntity>) -> CoordinatorMessage {
     CoordinatorMessage::Feedback(
         FeedbackData::GuiMessagePassthrough(
             GuiMessage::DisplayResults(
-                SearchMessageData { results }
+                SearchMessageData::composed(results)
             )
         )
     )
@@ -145,7 +183,8 @@ fn display_virt_command(
     feedback_tx: &mpsc::UnboundedSender<CoordinatorMessage>,
 ) -> bool {
     let send_virt_res = if query == ":wall" {
-        Some(compose_fb_help_msg(vec!["Random wallpaper!".into()]))
+        let entity = VirtualEntity::with_action("Random wallpaper!".into(), VirtualAction::RandomWallpaper);
+        Some(compose_fb_results_msg(vec![LauncherEntity::from_virtual(&entity)]))
     }
     else { None };
 
@@ -4,7 +4,8 @@ use tokio::sync::mpsc::UnboundedSender;
 use crate::coordinator::types::{CoordinatorMessage, GuiMessage};
 use crate::ui_gtk4::types::{ShunpoState, ShunpoWidgets, UIMode};
 use crate::ui_gtk4::ui_updaters::{
-    search_results::update_results,
+    search_results::{set_results_private, update_results},
+    status_segment::{update_job, update_launch_failed, update_status_segment},
     workspace_widget::update_active_workspace,
     ui_mode::ui_mode_from_gui_message,
 };
@@ -19,8 +20,41 @@ pub fn handle_ui_message(
         GuiMessage::UpdateWorkspace(workspaces) => {
             update_active_workspace(workspaces, widgets, state);
         },
+        GuiMessage::UpdateUrgent(text) => {
+            update_status_segment(&widgets.urgent, text);
+        },
+        GuiMessage::UpdateActiveWindow(text) => {
+            update_status_segment(&widgets.active_window, text);
+        },
+        GuiMessage::UpdateSubmap(text) => {
+            update_status_segment(&widgets.submap, text);
+        },
+        GuiMessage::UpdateKeyboardLayout(text) => {
+            update_status_segment(&widgets.keyboard_layout, text);
+        },
+        GuiMessage::UpdateScreencast { text, hide_results } => {
+            update_status_segment(&widgets.screencast, text);
+            set_results_private(widgets, hide_results);
+        },
+        GuiMessage::LaunchFailed(text) => {
+            update_launch_failed(&widgets.launch_failed, text);
+        },
+        GuiMessage::UpdateJob(name) => {
+            // jobs are started with Return, their output replaces the results it was chosen from
+            if name.is_some() {
+                state.job_view = true;
+            }
+            update_job(&widgets.job, name);
+        },
+        GuiMessage::JobOutput(data) => {
+            if state.job_view && state.prompt.is_none() {
+                update_results(data, widgets, state);
+            }
+        },
         GuiMessage::DisplayResults(data) => {
-            update_results(data, widgets, state);
+            if data.is_current(&widgets.search.text(), state.prompt.is_some()) {
+                update_results(data, widgets, state);
+            }
         },
         GuiMessage::UiTransitionToLauncher => {
             widgets.window.set_layer(gtk4_layer_shell::Layer::Overlay);
@@ -1,24 +1,45 @@
 use gtk4::{ApplicationWindow, Entry, Label, ListBox, Scale, ScrolledWindow};
 
-use crate::{coordinator::types::WorkspaceMessage, search::entity_model::LauncherEntity};
+use crate::{coordinator::types::MonitorWorkspacesMessage, search::{arg_prompt::ArgPrompt, entity_model::LauncherEntity}};
 
 pub struct ShunpoWidgets {
     pub window: ApplicationWindow,
     #[allow(dead_code)]
     pub clock: Label,
     pub workspaces: gtk4::Box,
+    pub urgent: Label,
+    pub active_window: Label,
+    pub submap: Label,
+    pub keyboard_layout: Label,
+    pub screencast: Label,
+    /// Last launch error, shown in clock and launcher mode until it times out
+    pub launch_failed: Label,
+    /// Running background job, shown in clock and launcher mode
+    pub job: Label,
     pub volume: Scale,
+    /// Name of the argument being entered, shown before the search field
+    pub prompt: Label,
     pub search: Entry,
     pub results: ListBox,
     pub results_window: ScrolledWindow,
 }
+impl ShunpoWidgets {
+    /// Optional clock-mode labels, shown only while they have text.
+    pub fn status_segments(&self) -> Vec<&Label> {
+        vec![&self.active_window, &self.urgent, &self.submap, &self.keyboard_layout, &self.screencast]
+    }
+}
 
 
 pub struct ShunpoState {
     pub ui_mode: UIMode,
     pub ui_transition: bool,
-    pub workspaces_data: Vec<WorkspaceMessage>,
+    pub workspaces_data: Vec<MonitorWorkspacesMessage>,
     pub results_data: Vec<LauncherEntity>,
+    /// Argument entry in progress
+    pub prompt: Option<ArgPrompt>,
+    /// Results show the output of the job started from the launcher, until the query changes
+    pub job_view: bool,
 }
 impl ShunpoState {
     pub fn new() -> Self {
@@ -27,6 +48,8 @@ impl ShunpoState {
             ui_transition: false,
             results_data: Vec::new(),
             workspaces_data: Vec::new(),
+            prompt: None,
+            job_view: false,
         }
     }
 }
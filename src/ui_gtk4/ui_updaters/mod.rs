@@ -1,4 +1,5 @@
 pub mod common;
 pub mod search_results;
+pub mod status_segment;
 pub mod workspace_widget;
 pub mod ui_mode;
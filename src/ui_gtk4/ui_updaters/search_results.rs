@@ -1,10 +1,10 @@
 use gtk4::prelude::*;
 use gtk4::{
-    Label, Box, Orientation,
+    Label, Box, Image, Orientation,
     ListBoxRow
 };
 
-use crate::search::entity_model::{ExecutableEntity, FileEntity, RipgrepEntity, VirtualEntity};
+use crate::search::entity_model::{ExecutableEntity, FileEntity, HyprCommandEntity, ProviderEntity, RipgrepEntity, VirtualEntity};
 use crate::{coordinator::types::SearchMessageData, ui_gtk4::types::{ShunpoState, ShunpoWidgets}};
 
 pub fn update_results(
@@ -33,6 +33,12 @@ pub fn update_results(
             FileEntity::Virtual(virtual_entity) => {
                 row_from_virt(&virtual_entity)
             },
+            FileEntity::HyprCommand(command_entity) => {
+                row_from_hypr_command(&command_entity)
+            },
+            FileEntity::Provider(provider_entity) => {
+                row_from_provider(&provider_entity)
+            },
         };
 
         widgets.results.append(&row);
@@ -46,12 +52,55 @@ pub fn update_results(
     }
 }
 
+/// Blur results, e.g. while the screen is shared
+pub fn set_results_private(widgets: &ShunpoWidgets, private: bool) {
+    if private {
+        widgets.results_window.add_css_class("results-private");
+    } else {
+        widgets.results_window.remove_css_class("results-private");
+    }
+}
+
 fn row_from_exec(entity: &ExecutableEntity) -> ListBoxRow {
+    let Some(script) = &entity.script else {
+        let row = ListBoxRow::new();
+        let hbox = new_listbox_hbox();
+        let label = Label::new(Some(&entity.ui_name));
+        hbox.append(&label);
+        row.set_child(Some(&hbox));
+        return row;
+    };
+
+    row_with_details(&entity.ui_name, script.description.as_deref(), script.icon.as_deref())
+}
+
+fn row_from_provider(entity: &ProviderEntity) -> ListBoxRow {
+    row_with_details(&entity.ui_name, entity.subtitle.as_deref(), entity.icon.as_deref())
+}
+
+/// Name with an optional icon before it and description below it
+fn row_with_details(name: &str, description: Option<&str>, icon: Option<&str>) -> ListBoxRow {
     let row = ListBoxRow::new();
     let hbox = new_listbox_hbox();
 
-    let label = Label::new(Some(&entity.ui_name));
-    hbox.append(&label);
+    if let Some(icon) = icon {
+        hbox.append(&Image::from_icon_name(icon));
+    }
+
+    let vbox = Box::new(Orientation::Vertical, 2);
+    vbox.set_hexpand(true);
+
+    let label = Label::new(Some(name));
+    label.set_halign(gtk4::Align::Start);
+    vbox.append(&label);
+
+    if let Some(description) = description {
+        let description = Label::new(Some(description));
+        description.set_halign(gtk4::Align::Start);
+        description.add_css_class("result-description");
+        vbox.append(&description);
+    }
+    hbox.append(&vbox);
 
     row.set_child(Some(&hbox));
     row
@@ -92,6 +141,17 @@ fn row_from_virt(entity: &VirtualEntity) -> ListBoxRow {
     row
 }
 
+fn row_from_hypr_command(entity: &HyprCommandEntity) -> ListBoxRow {
+    let row = ListBoxRow::new();
+    let hbox = new_listbox_hbox();
+
+    let label = Label::new(Some(&entity.ui_name));
+    hbox.append(&label);
+
+    row.set_child(Some(&hbox));
+    row
+}
+
 fn new_listbox_hbox() -> Box {
     let hbox = Box::new(Orientation::Horizontal, 10);
     hbox.set_margin_top(5);
@@ -0,0 +1,31 @@
+use gtk4::{glib, prelude::*, Label};
+use std::time::Duration;
+
+/// How long a launch failure stays visible
+const LAUNCH_FAILED_TIMEOUT: Duration = Duration::from_secs(6);
+
+/// Set the text of an optional clock-mode label. Visibility follows in toggle_ui_mode().
+pub fn update_status_segment(label: &Label, text: Option<String>) {
+    label.set_text(text.as_deref().unwrap_or(""));
+}
+
+/// Show a launch failure, clearing it after a timeout unless a newer failure replaced it.
+pub fn update_launch_failed(label: &Label, text: String) {
+    label.set_text(&text);
+    label.set_visible(true);
+
+    let label = label.clone();
+    glib::timeout_add_local_once(LAUNCH_FAILED_TIMEOUT, move || {
+        if label.text() == text {
+            label.set_text("");
+            label.set_visible(false);
+        }
+    });
+}
+
+/// Show the running background job, hidden once it finished
+pub fn update_job(label: &Label, name: Option<String>) {
+    let text = name.map(|name| format!("⟳ {}", name)).unwrap_or_default();
+    label.set_text(&text);
+    label.set_visible(!text.is_empty());
+}
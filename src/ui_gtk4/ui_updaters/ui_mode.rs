@@ -19,6 +19,12 @@ pub fn ui_mode_from_gui_message(
 ) {
     // shadow msg on toggle messages
     let msg = match msg {
+        GuiMessage::LaunchFailed(_)=> {
+            panic!("UI status invariant: GuiMessage::LaunchFailed should have been caught earlier.");
+        },
+        GuiMessage::UpdateJob(_)=> {
+            panic!("UI status invariant: GuiMessage::UpdateJob should have been caught earlier.");
+        },
         GuiMessage::ToggleUiMode => { 
             match state.ui_mode {
                 UIMode::Launcher => GuiMessage::Sleep,
@@ -104,12 +110,30 @@ pub fn ui_mode_from_gui_message(
         GuiMessage::UpdateWorkspace(_)=> {
             panic!("UI workspace invariant: GuiMessage::UpdateWorkspace should have been caught earlier.");
         },
+        GuiMessage::UpdateUrgent(_)=> {
+            panic!("UI status invariant: GuiMessage::UpdateUrgent should have been caught earlier.");
+        },
+        GuiMessage::UpdateActiveWindow(_)=> {
+            panic!("UI status invariant: GuiMessage::UpdateActiveWindow should have been caught earlier.");
+        },
+        GuiMessage::UpdateSubmap(_)=> {
+            panic!("UI status invariant: GuiMessage::UpdateSubmap should have been caught earlier.");
+        },
+        GuiMessage::UpdateKeyboardLayout(_)=> {
+            panic!("UI status invariant: GuiMessage::UpdateKeyboardLayout should have been caught earlier.");
+        },
+        GuiMessage::UpdateScreencast { .. }=> {
+            panic!("UI status invariant: GuiMessage::UpdateScreencast should have been caught earlier.");
+        },
         GuiMessage::ToggleUiMode => {
             panic!("UI mode switch invariant: GuiMessage::ToggleUiMode should have been translated.");
         },
         GuiMessage::DisplayResults(_) => {
             panic!("UI mode switch invariant: GuiMessage::DisplayResults");
         },
+        GuiMessage::JobOutput(_) => {
+            panic!("UI mode switch invariant: GuiMessage::JobOutput");
+        },
     };
 
     widgets.window.set_layer(layer);
@@ -120,6 +144,10 @@ pub fn ui_mode_from_gui_message(
     match msg {
         GuiMessage::Wake => {
             widgets.search.grab_focus();
+            // drop an unfinished argument prompt before clearing, so the search runs again
+            state.prompt = None;
+            state.job_view = false;
+            widgets.prompt.set_visible(false);
             widgets.search.set_text(""); // clear previous search
             if let Ok(vol) = system::volume::get_volume() { // update volume controller
                 widgets.volume.set_value(vol.into());
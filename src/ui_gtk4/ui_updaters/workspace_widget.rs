@@ -1,26 +1,49 @@
 use gtk4::{Label, Orientation, prelude::{BoxExt, WidgetExt}};
 
-use crate::{coordinator::types::WorkspaceMessage, ui_gtk4::{types::{ShunpoState, ShunpoWidgets}, ui_updaters::common::clear_workspaces}};
+use crate::{coordinator::types::{MonitorWorkspacesMessage, WorkspaceMessage}, ui_gtk4::{types::{ShunpoState, ShunpoWidgets}, ui_updaters::common::clear_workspaces}};
 
 pub fn update_active_workspace(
-    workspaces: Vec<WorkspaceMessage>,
+    monitors: Vec<MonitorWorkspacesMessage>,
     widgets: &ShunpoWidgets,
     state: &mut ShunpoState,
 ) {
     clear_workspaces(widgets);
 
     // update state
-    state.workspaces_data = workspaces.clone();
+    state.workspaces_data = monitors.clone();
 
-    // populate new workspace indicators
-    for workspace in workspaces {
-        let ws_box = gtk4::Box::new(Orientation::Horizontal, 0);
-        ws_box.add_css_class(if workspace.focused { "ws-active-bg" } else { "ws-inactive-bg" });
+    // populate new workspace indicators, grouped by monitor
+    for monitor in monitors {
+        let monitor_box = gtk4::Box::new(Orientation::Horizontal, 0);
+        monitor_box.add_css_class("ws-monitor");
 
-        let label = Label::new(Some(&workspace.id));
-        label.add_css_class("ws-label");
-        ws_box.append(&label);
+        for workspace in &monitor.workspaces {
+            monitor_box.append(&workspace_indicator(workspace));
+        }
 
-        widgets.workspaces.append(&ws_box);
+        widgets.workspaces.append(&monitor_box);
     }
 }
+
+fn workspace_indicator(workspace: &WorkspaceMessage) -> gtk4::Box {
+    let ws_box = gtk4::Box::new(Orientation::Horizontal, 0);
+
+    ws_box.add_css_class(
+        if workspace.focused { "ws-active-bg" }
+        else if workspace.active { "ws-visible-bg" }
+        else { "ws-inactive-bg" }
+    );
+    ws_box.add_css_class(if workspace.occupied { "ws-occupied" } else { "ws-empty" });
+    if workspace.urgent {
+        ws_box.add_css_class("ws-urgent");
+    }
+    if workspace.special {
+        ws_box.add_css_class("ws-special");
+    }
+
+    let label = Label::new(Some(&workspace.label));
+    label.add_css_class("ws-label");
+    ws_box.append(&label);
+
+    ws_box
+}
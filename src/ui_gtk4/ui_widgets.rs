@@ -51,6 +51,14 @@ pub fn clock() -> Label {
 
     clock_label
 }
+/// Optional clock-mode label, hidden until it receives text
+pub fn status_segment(css_class: &str) -> Label {
+    let label = Label::new(None);
+    label.add_css_class("status-segment");
+    label.add_css_class(css_class);
+    label.set_visible(false);
+    label
+}
 pub fn workspaces() -> gtk4::Box {
     let container = gtk4::Box::new(Orientation::Horizontal, 0);
     container.add_css_class("workspaces");
@@ -100,3 +108,11 @@ pub fn results() -> (ScrolledWindow, ListBox) {
 
     (scrolled_window, results_list)
 }
+
+/// Argument prompt label, visible while arguments are entered
+pub fn prompt() -> Label {
+    let label = Label::new(None);
+    label.add_css_class("prompt-label");
+    label.set_visible(false);
+    label
+}
@@ -27,6 +27,8 @@ pub fn toggle_ui_mode(widgets: &ShunpoWidgets, state: &ShunpoState) {
             widgets.clock.set_visible(true);
             widgets.volume.set_visible(false);
             widgets.workspaces.set_visible(true);
+            show_status_segments(widgets, true);
+            show_launch_status(widgets, true);
             widgets.search.set_visible(false);
             widgets.results.set_visible(false);
             widgets.results_window.set_visible(false);
@@ -41,6 +43,8 @@ pub fn toggle_ui_mode(widgets: &ShunpoWidgets, state: &ShunpoState) {
             widgets.clock.set_visible(true);
             widgets.volume.set_visible(true);
             widgets.workspaces.set_visible(false);
+            show_status_segments(widgets, false);
+            show_launch_status(widgets, true);
             widgets.search.set_visible(true);
             widgets.results.set_visible(true);
             widgets.results_window.set_visible(true);
@@ -55,6 +59,8 @@ pub fn toggle_ui_mode(widgets: &ShunpoWidgets, state: &ShunpoState) {
             widgets.clock.set_visible(false);
             widgets.volume.set_visible(false);
             widgets.workspaces.set_visible(false);
+            show_status_segments(widgets, false);
+            show_launch_status(widgets, false);
             widgets.search.set_visible(false);
             widgets.results.set_visible(false);
             widgets.results_window.set_visible(false);
@@ -69,9 +75,25 @@ pub fn toggle_ui_mode(widgets: &ShunpoWidgets, state: &ShunpoState) {
             widgets.clock.set_visible(false);
             widgets.volume.set_visible(false);
             widgets.workspaces.set_visible(false);
+            show_status_segments(widgets, false);
+            show_launch_status(widgets, false);
             widgets.search.set_visible(false);
             widgets.results.set_visible(false);
             widgets.results_window.set_visible(false);
         },
     };
 }
+
+/// Show status segments which have text, or hide all of them
+fn show_status_segments(widgets: &ShunpoWidgets, show: bool) {
+    for segment in widgets.status_segments() {
+        segment.set_visible(show && !segment.text().is_empty());
+    }
+}
+
+/// Show the launch failure message and running job while they have text
+fn show_launch_status(widgets: &ShunpoWidgets, show: bool) {
+    for label in [&widgets.launch_failed, &widgets.job] {
+        label.set_visible(show && !label.text().is_empty());
+    }
+}